@@ -100,6 +100,12 @@ fn main() -> Result<(), Box<dyn Error>> {
             Some(Validator::Enumeration(
                     vec![String::from("quad"), String::from("octal")]
             )),
+        ),
+        (
+            "i2c-default-frequency",
+            "The bus frequency in Hz used by the default I2C master configuration.",
+            Value::Integer(100_000),
+            Some(Validator::PositiveInteger),
         )
     ], true);
 
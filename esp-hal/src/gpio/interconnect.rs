@@ -225,6 +225,30 @@ fn disconnect_peripheral_output_from_pin(pin: &AnyPin, signal: gpio::OutputSigna
         .write(|w| w.sel().clear_bit());
 }
 
+/// Sets whether the value of an input signal that is routed through the GPIO
+/// matrix is inverted.
+///
+/// This changes the inversion of an existing connection, e.g. to follow a
+/// driver configuration change. It has no effect on signals that are
+/// connected through the IO MUX.
+pub(crate) fn set_input_signal_inverted(signal: gpio::InputSignal, invert: bool) {
+    GPIO::regs()
+        .func_in_sel_cfg(signal as usize - FUNC_IN_SEL_OFFSET)
+        .modify(|_, w| w.in_inv_sel().bit(invert));
+}
+
+/// Sets whether the peripheral output signal that is routed to `pin` through
+/// the GPIO matrix is inverted.
+///
+/// This changes the inversion of an existing connection, e.g. to follow a
+/// driver configuration change. It has no effect on signals that are
+/// connected through the IO MUX.
+pub(crate) fn set_output_signal_inverted(pin: u8, invert: bool) {
+    GPIO::regs()
+        .func_out_sel_cfg(pin as usize)
+        .modify(|_, w| w.inv_sel().bit(invert));
+}
+
 /// A configurable input signal between a peripheral and a GPIO pin.
 ///
 /// Multiple input signals can be connected to one pin.
@@ -611,6 +635,15 @@ impl InputConnection {
             fn connect_input_to_peripheral(&self, signal: gpio::InputSignal);
         }
     }
+
+    /// Returns the number of the connected pin, if any.
+    pub(crate) fn pin_number(&self) -> Option<u8> {
+        match &self.0 {
+            InputConnectionInner::Input(signal) => Some(signal.pin.number()),
+            InputConnectionInner::DirectInput(signal) => Some(signal.pin.number()),
+            InputConnectionInner::Constant(_) => None,
+        }
+    }
 }
 
 enum OutputConnectionInner {
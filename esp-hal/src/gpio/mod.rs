@@ -157,6 +157,18 @@ impl PinGuard {
             signal,
         }
     }
+
+    /// Returns the number of the connected pin, if any.
+    pub(crate) fn pin_number(&self) -> Option<u8> {
+        (self.pin != u8::MAX).then_some(self.pin)
+    }
+
+    /// Returns whether the input level of the connected pin is high, if a pin
+    /// is connected.
+    pub(crate) fn is_input_high(&self) -> Option<bool> {
+        self.pin_number()
+            .map(|pin| unsafe { AnyPin::steal(pin) }.is_input_high())
+    }
 }
 
 impl Drop for PinGuard {
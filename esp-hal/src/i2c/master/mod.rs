@@ -19,8 +19,16 @@
 //! from the community, including the [embedded-hal].
 //!
 //! [embedded-hal]: embedded_hal
+//!
+//! To share a bus between async tasks, see the `shared` module.
+//! For devices with 8-bit register maps, see the `register_device` module.
+
+#![allow(
+    private_interfaces,
+    reason = "BitOrder and EmptyRead are unstable, but Config and its builder functions need to be public"
+)]
 
-use core::marker::PhantomData;
+use core::{future::Future, marker::PhantomData, mem::MaybeUninit, ops::Range};
 #[cfg(not(esp32))]
 use core::{
     pin::Pin,
@@ -32,28 +40,44 @@ use embassy_embedded_hal::SetConfig;
 use embedded_hal::i2c::Operation as EhalOperation;
 use enumset::{EnumSet, EnumSetType};
 use fugit::HertzU32;
+use portable_atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
 
 use crate::{
     asynch::AtomicWaker,
     clock::Clocks,
     gpio::{
-        interconnect::{OutputConnection, PeripheralOutput},
+        interconnect::{
+            set_input_signal_inverted,
+            set_output_signal_inverted,
+            OutputConnection,
+            PeripheralInput,
+            PeripheralOutput,
+        },
+        AnyPin,
         InputSignal,
         OutputSignal,
         PinGuard,
         Pull,
     },
-    interrupt::{InterruptConfigurable, InterruptHandler},
+    interrupt::{InterruptConfigurable, InterruptHandler, Priority},
     pac::i2c0::{RegisterBlock, COMD},
     peripheral::{Peripheral, PeripheralRef},
     peripherals::Interrupt,
     private,
+    sync::Locked,
     system::{PeripheralClockControl, PeripheralGuard},
+    time::{Duration, Instant},
     Async,
     Blocking,
+    Cpu,
     DriverMode,
 };
 
+crate::unstable_module! {
+    pub mod register_device;
+    pub mod shared;
+}
+
 cfg_if::cfg_if! {
     if #[cfg(esp32s2)] {
         const I2C_LL_INTR_MASK: u32 = 0x1ffff;
@@ -62,15 +86,68 @@ cfg_if::cfg_if! {
     }
 }
 
-// Chunk writes/reads by this size
+// Chunk writes/reads by these sizes. On the ESP32 and ESP32-S2 the chunks are
+// limited by the 32-byte FIFO, which also holds the address byte of a write.
+#[cfg(any(esp32, esp32s2))]
+const I2C_WRITE_CHUNK_SIZE: usize = 31;
+#[cfg(any(esp32, esp32s2))]
+const I2C_READ_CHUNK_SIZE: usize = 32;
+
+// On the other chips both are limited by the 8-bit length of a command, so they
+// stay equal. The byte taken by the address of a write or by the NACK of a read
+// is accounted for by `max_write_chunk_len` and `read_chunks`.
+#[cfg(not(any(esp32, esp32s2)))]
+const I2C_WRITE_CHUNK_SIZE: usize = 255;
+#[cfg(not(any(esp32, esp32s2)))]
+const I2C_READ_CHUNK_SIZE: usize = 255;
+
+// The highest bus frequency the controller is documented to support
+cfg_if::cfg_if! {
+    if #[cfg(any(esp32c2, esp32c3, esp32s3))] {
+        const I2C_MAX_FREQUENCY: u32 = 800_000;
+    } else {
+        // Fast Mode Plus
+        const I2C_MAX_FREQUENCY: u32 = 1_000_000;
+    }
+}
+
+// The largest value the SDA and SCL setup/hold/sample timing fields can hold
 #[cfg(any(esp32, esp32s2))]
-const I2C_CHUNK_SIZE: usize = 32;
+const MAX_TIMING_CYCLES: u32 = 0x3FF;
+
+#[cfg(not(any(esp32, esp32s2)))]
+const MAX_TIMING_CYCLES: u32 = 0x1FF;
+
+// The largest value the SCL wait-high period field can hold
+#[cfg(esp32s2)]
+const MAX_SCL_WAIT_HIGH: u32 = 0x3FFF;
 
 #[cfg(not(any(esp32, esp32s2)))]
-const I2C_CHUNK_SIZE: usize = 254;
+const MAX_SCL_WAIT_HIGH: u32 = 0x7F;
+
+// How often a transaction is retried after losing the arbitration in
+// multi-master mode
+const MAX_ARBITRATION_RETRIES: usize = 8;
+
+// How long to wait for another master to release the bus in multi-master mode
+const BUS_IDLE_TIMEOUT: Duration = Duration::millis(10);
+
+// How long `device_present` waits between probes, so that the samples span
+// the contact bounce of a connector instead of all being taken within it
+const PRESENCE_SETTLE_TIME: Duration = Duration::millis(5);
 
-// on ESP32 there is a chance to get trapped in `wait_for_completion` forever
-const MAX_ITERATIONS: u32 = 1_000_000;
+// The SMBus Alert Response Address
+const SMBUS_ALERT_RESPONSE_ADDRESS: u8 = 0x0C;
+
+// The bus frequency of `Config::default()`, in Hz
+const DEFAULT_FREQUENCY: u32 =
+    esp_config::esp_config_int!(u32, "ESP_HAL_CONFIG_I2C_DEFAULT_FREQUENCY");
+
+// How many times the worst-case transfer duration we wait in
+// `wait_for_completion` before giving up. On ESP32 there is a chance to get
+// trapped there forever, so we need a software timeout on top of the bus
+// timeout.
+const COMPLETION_TIMEOUT_FACTOR: u64 = 4;
 
 /// Representation of I2C address.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -96,6 +173,61 @@ impl From<u8> for I2cAddress {
     }
 }
 
+/// A validated, right-aligned 7-bit I2C address.
+///
+/// Plain `u8` addresses are used as they are, so passing a left-aligned
+/// address that includes the R/W bit silently selects the wrong device. This
+/// type checks the address when it is created, and can be used anywhere an
+/// address is accepted. As the constructor is a `const fn`, the check can
+/// happen at compile time:
+///
+/// ```rust, no_run
+#[doc = crate::before_snippet!()]
+/// # use esp_hal::i2c::master::{Config, I2c, SevenBitAddress};
+/// const DEVICE_ADDR: SevenBitAddress = match SevenBitAddress::new(0x77) {
+///     Some(address) => address,
+///     None => panic!("invalid address"),
+/// };
+///
+/// let mut i2c = I2c::new(peripherals.I2C0, Config::default())
+///     .unwrap()
+///     .with_sda(peripherals.GPIO1)
+///     .with_scl(peripherals.GPIO2);
+/// i2c.write(DEVICE_ADDR, &[0xaa]).ok();
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub struct SevenBitAddress(u8);
+
+impl SevenBitAddress {
+    /// Creates a 7-bit address.
+    ///
+    /// Returns `None` for values above `0x7F`, which are most likely
+    /// left-aligned addresses, and for the addresses reserved by the I2C
+    /// specification (`0x00..=0x07` and `0x78..=0x7F`). Use a plain `u8` and
+    /// [`Config::allow_reserved_addresses`] to talk to a reserved address,
+    /// e.g. for a general call.
+    pub const fn new(address: u8) -> Option<Self> {
+        match address {
+            0x08..=0x77 => Some(Self(address)),
+            _ => None,
+        }
+    }
+
+    /// Returns the address as a right-aligned `u8`.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<SevenBitAddress> for I2cAddress {
+    fn from(value: SevenBitAddress) -> Self {
+        I2cAddress::SevenBit(value.0)
+    }
+}
+
 /// I2C SCL timeout period.
 ///
 /// When the level of SCL remains unchanged for more than `timeout` bus
@@ -117,7 +249,9 @@ pub enum BusTimeout {
     Maximum,
 
     /// Disable timeout control.
-    #[cfg(not(any(esp32, esp32s2)))]
+    ///
+    /// The ESP32 and ESP32-S2 can't disable the timeout in hardware. On these
+    /// chips this is equivalent to [`BusTimeout::Maximum`].
     Disabled,
 
     /// Timeout in bus clock cycles.
@@ -128,10 +262,10 @@ impl BusTimeout {
     fn cycles(&self) -> u32 {
         match self {
             #[cfg(esp32)]
-            BusTimeout::Maximum => 0xF_FFFF,
+            BusTimeout::Maximum | BusTimeout::Disabled => 0xF_FFFF,
 
             #[cfg(esp32s2)]
-            BusTimeout::Maximum => 0xFF_FFFF,
+            BusTimeout::Maximum | BusTimeout::Disabled => 0xFF_FFFF,
 
             #[cfg(not(any(esp32, esp32s2)))]
             BusTimeout::Maximum => 0x1F,
@@ -149,6 +283,235 @@ impl BusTimeout {
     }
 }
 
+/// I2C bit order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub enum BitOrder {
+    /// Most Significant Bit (MSB) is transmitted first, as required by the I2C
+    /// specification.
+    MsbFirst,
+    /// Least Significant Bit (LSB) is transmitted first.
+    LsbFirst,
+}
+
+/// How [`I2c::transaction`] handles read operations with an empty buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub enum EmptyRead {
+    /// Empty reads are skipped without accessing the bus.
+    Skip,
+    /// Empty reads are rejected with [`Error::ZeroLengthInvalid`].
+    Error,
+    /// An empty read is executed as a quick read: a START condition, the
+    /// address with the read bit set and a STOP condition. This can be used to
+    /// probe devices that only acknowledge read requests.
+    ///
+    /// The hardware can't end a read right after the address in the middle of
+    /// a transaction, so this is only supported if the empty read is the only
+    /// operation of the transaction. If the transaction contains any other
+    /// operation, including a delay, the empty read is rejected with
+    /// [`Error::ZeroLengthInvalid`] like with [`EmptyRead::Error`]. The quick
+    /// read always ends with a STOP condition, even in transactions that
+    /// otherwise leave the bus claimed.
+    Probe,
+}
+
+/// State of the I2C bus lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+#[instability::unstable]
+pub enum BusState {
+    /// No transaction is in progress and both lines are high.
+    Idle,
+
+    /// A transaction is in progress on the bus.
+    Busy,
+
+    /// No transaction is in progress, but SDA is held low.
+    SdaStuckLow,
+
+    /// No transaction is in progress, but SCL is held low.
+    SclStuckLow,
+}
+
+/// A fault found on a bus line by [`I2c::check_bus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+#[instability::unstable]
+pub enum LineFault {
+    /// The line doesn't read high when released.
+    ///
+    /// Either the line has no pull-up, or a device is holding it low.
+    NotPulledUp,
+
+    /// The line doesn't read low while it is driven low.
+    ///
+    /// This usually means that the pin isn't connected to the line, or that
+    /// the line is shorted to the supply.
+    NotDrivenLow,
+}
+
+impl core::fmt::Display for LineFault {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LineFault::NotPulledUp => write!(f, "not pulled up"),
+            LineFault::NotDrivenLow => write!(f, "can't be driven low"),
+        }
+    }
+}
+
+/// The faults found by [`I2c::check_bus`].
+///
+/// At least one of the lines has a fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub struct BusCheckError {
+    /// The fault found on SDA, if any.
+    pub sda: Option<LineFault>,
+
+    /// The fault found on SCL, if any.
+    pub scl: Option<LineFault>,
+}
+
+impl core::error::Error for BusCheckError {}
+
+impl core::fmt::Display for BusCheckError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.sda, self.scl) {
+            (Some(sda), Some(scl)) => write!(f, "SDA is {} and SCL is {}", sda, scl),
+            (Some(sda), None) => write!(f, "SDA is {}", sda),
+            (None, Some(scl)) => write!(f, "SCL is {}", scl),
+            (None, None) => write!(f, "No bus fault"),
+        }
+    }
+}
+
+/// The error flags of the peripheral, as returned by [`I2c::error_flags`].
+///
+/// These are the raw signals the driver inspects to decide which [`Error`] to
+/// report. More than one of them may be set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+#[non_exhaustive]
+pub struct BusErrorFlags {
+    /// The bus timeout expired.
+    pub timeout: bool,
+
+    /// A byte was not acknowledged while the ACK was checked.
+    pub nack: bool,
+
+    /// The arbitration for the bus was lost.
+    pub arbitration_lost: bool,
+
+    /// The command sequence completed, but the last byte sent was not
+    /// acknowledged. Always `false` on the ESP32, which can't detect this.
+    pub data_nack: bool,
+}
+
+impl BusErrorFlags {
+    const TIMEOUT: u8 = 1 << 0;
+    const NACK: u8 = 1 << 1;
+    const ARBITRATION_LOST: u8 = 1 << 2;
+    const DATA_NACK: u8 = 1 << 3;
+
+    /// Reads the flags from the interrupt and status registers.
+    fn read(register_block: &RegisterBlock) -> Self {
+        let interrupts = register_block.int_raw().read();
+
+        Self {
+            timeout: interrupts.time_out().bit_is_set(),
+            nack: interrupts.nack().bit_is_set(),
+            arbitration_lost: interrupts.arbitration_lost().bit_is_set(),
+            #[cfg(not(esp32))]
+            data_nack: interrupts.trans_complete().bit_is_set()
+                && register_block.sr().read().resp_rec().bit_is_clear(),
+            #[cfg(esp32)]
+            data_nack: false,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        let mut bits = 0;
+        for (set, bit) in [
+            (self.timeout, Self::TIMEOUT),
+            (self.nack, Self::NACK),
+            (self.arbitration_lost, Self::ARBITRATION_LOST),
+            (self.data_nack, Self::DATA_NACK),
+        ] {
+            if set {
+                bits |= bit;
+            }
+        }
+        bits
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            timeout: bits & Self::TIMEOUT != 0,
+            nack: bits & Self::NACK != 0,
+            arbitration_lost: bits & Self::ARBITRATION_LOST != 0,
+            data_nack: bits & Self::DATA_NACK != 0,
+        }
+    }
+
+    /// Saves the current flags as the flags of the last failure.
+    fn record(register_block: &RegisterBlock, state: &State) {
+        state
+            .last_error_flags
+            .store(Self::read(register_block).bits(), Ordering::Relaxed);
+    }
+}
+
+/// The error returned by [`I2c::transaction_detailed`].
+///
+/// Besides the error itself, it identifies the operation that failed and how
+/// far it got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub struct TransactionError {
+    /// The error that ended the transaction.
+    pub error: Error,
+
+    /// The index of the failed operation in the operations passed to the
+    /// transaction.
+    pub operation: usize,
+
+    /// The number of bytes of the failed operation that were transferred
+    /// before the error.
+    ///
+    /// Operations are transferred in chunks, and only completed chunks are
+    /// counted. The bytes of the chunk that failed may have been partially
+    /// transferred.
+    #[cfg_attr(
+        any(esp32, esp32s2),
+        doc = "\n\nOn this chip, a chunk is up to 32 bytes long."
+    )]
+    #[cfg_attr(
+        not(any(esp32, esp32s2)),
+        doc = "\n\nOn this chip, a chunk is up to 255 bytes long, or 254 bytes if it also carries the address or the final NACKed byte of a read."
+    )]
+    pub transferred: usize,
+}
+
+impl core::error::Error for TransactionError {}
+
+impl core::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} (operation {}, after {} bytes)",
+            self.error, self.operation, self.transferred
+        )
+    }
+}
+
 /// I2C-specific transmission errors
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -156,10 +519,29 @@ impl BusTimeout {
 pub enum Error {
     /// The transmission exceeded the FIFO size.
     FifoExceeded,
+    /// A single read or write command was longer than the command length
+    /// field allows.
+    ///
+    /// Unlike [`Error::FifoExceeded`], this isn't caused by the size of the
+    /// FIFO but by the size of the length field of a command.
+    TransferTooLong {
+        /// The maximum length of the command, in bytes.
+        max: usize,
+    },
     /// The acknowledgment check failed.
     AcknowledgeCheckFailed(AcknowledgeCheckFailedReason),
     /// A timeout occurred during transmission.
     Timeout,
+    /// A device held SCL low for longer than the configured [`BusTimeout`].
+    ///
+    /// This is reported instead of [`Error::Timeout`] when SCL is still low
+    /// after the bus timeout expired, which means a slave is stretching the
+    /// clock. This is legitimate behavior, and the fix is usually to increase
+    /// the bus timeout.
+    ///
+    /// Detecting this needs the level of the SCL pin, so it is only reported
+    /// if SCL is connected to a single open drain pin.
+    ClockStretchTimeout,
     /// The arbitration for the bus was lost.
     ArbitrationLost,
     /// The execution of the I2C command was incomplete.
@@ -168,6 +550,9 @@ pub enum Error {
     CommandNumberExceeded,
     /// Zero length read or write operation.
     ZeroLengthInvalid,
+    /// The address is reserved by the I2C specification, and
+    /// [`Config::allow_reserved_addresses`] is not set.
+    ReservedAddress,
 }
 
 /// I2C no acknowledge error reason.
@@ -219,10 +604,20 @@ impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::FifoExceeded => write!(f, "The transmission exceeded the FIFO size"),
+            Error::TransferTooLong { max } => {
+                write!(
+                    f,
+                    "The transfer exceeded the maximum length of {} bytes",
+                    max
+                )
+            }
             Error::AcknowledgeCheckFailed(reason) => {
                 write!(f, "The acknowledgment check failed. Reason: {}", reason)
             }
             Error::Timeout => write!(f, "A timeout occurred during transmission"),
+            Error::ClockStretchTimeout => {
+                write!(f, "A device held SCL low for longer than the bus timeout")
+            }
             Error::ArbitrationLost => write!(f, "The arbitration for the bus was lost"),
             Error::ExecutionIncomplete => {
                 write!(f, "The execution of the I2C command was incomplete")
@@ -231,6 +626,7 @@ impl core::fmt::Display for Error {
                 write!(f, "The number of commands issued exceeded the limit")
             }
             Error::ZeroLengthInvalid => write!(f, "Zero length read or write operation"),
+            Error::ReservedAddress => write!(f, "The address is reserved"),
         }
     }
 }
@@ -244,6 +640,10 @@ pub enum ConfigError {
     FrequencyInvalid,
     /// Provided timeout is invalid for the current configuration.
     TimeoutInvalid,
+    /// Provided timing override does not fit into the timing register.
+    TimingInvalid,
+    /// Provided duty cycle violates the minimum SCL low or high time.
+    DutyCycleInvalid,
 }
 
 impl core::error::Error for ConfigError {}
@@ -259,6 +659,14 @@ impl core::fmt::Display for ConfigError {
                 f,
                 "Provided timeout is invalid for the current configuration"
             ),
+            ConfigError::TimingInvalid => write!(
+                f,
+                "Provided timing override does not fit into the timing register"
+            ),
+            ConfigError::DutyCycleInvalid => write!(
+                f,
+                "Provided duty cycle violates the minimum SCL low or high time"
+            ),
         }
     }
 }
@@ -268,15 +676,41 @@ impl core::fmt::Display for ConfigError {
 // determine whether a START condition should be issued at the start of the
 // current operation and whether a read needs an ack or a nack for the final
 // byte.
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 enum OpKind {
     Write,
     Read,
+    Delay,
+}
+
+// The position reached in a transaction, used to report where it failed.
+#[derive(Clone, Copy, Default)]
+struct Progress {
+    // Index of the operation being executed, in the caller's operations.
+    operation: usize,
+    // Bytes of that operation that were transferred successfully.
+    transferred: usize,
+}
+
+impl Progress {
+    fn error(self, error: Error) -> TransactionError {
+        TransactionError {
+            error,
+            operation: self.operation,
+            transferred: self.transferred,
+        }
+    }
 }
 
 /// I2C operation.
 ///
 /// Several operations can be combined as part of a transaction.
+///
+/// Comparing and hashing operations takes the current contents of their
+/// buffers into account, including the buffers of `Read` operations. Two reads
+/// into different buffers compare equal if the buffers have the same length
+/// and contents. To compare transactions by their structure only, compare
+/// their [`Operation::shape`] instead.
 #[derive(Debug, PartialEq, Eq, Hash, strum::Display)]
 pub enum Operation<'a> {
     /// Write data from the provided buffer.
@@ -284,6 +718,20 @@ pub enum Operation<'a> {
 
     /// Read data into the provided buffer.
     Read(&'a mut [u8]),
+
+    /// Release the bus and wait for the given number of microseconds.
+    ///
+    /// The preceding operation is terminated by a STOP condition and the
+    /// following operation starts with a new START condition. This means the
+    /// operations before and after the delay are not part of the same
+    /// transaction, and another master may use the bus in the meantime. Some
+    /// devices require this, e.g. to give them time to complete a
+    /// measurement between the command and reading back the result.
+    ///
+    /// In async mode, the delay waits on an `embassy-time` timer if the
+    /// `embassy-time` feature is enabled. Otherwise it busy-waits and blocks
+    /// the executor for its duration.
+    Delay(u32),
 }
 
 impl<'a, 'b> From<&'a mut embedded_hal::i2c::Operation<'b>> for Operation<'a> {
@@ -300,11 +748,41 @@ impl<'a, 'b> From<&'a mut Operation<'b>> for Operation<'a> {
         match value {
             Operation::Write(buffer) => Operation::Write(buffer),
             Operation::Read(buffer) => Operation::Read(buffer),
+            Operation::Delay(us) => Operation::Delay(*us),
         }
     }
 }
 
+/// The shape of an [`Operation`], without the contents of its buffer.
+///
+/// Transactions with equal shapes are executed the same way by the driver,
+/// which makes this suitable e.g. as a key for caching per-transaction data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub enum OperationShape {
+    /// A write of the given number of bytes.
+    Write(usize),
+
+    /// A read of the given number of bytes.
+    Read(usize),
+
+    /// A delay of the given number of microseconds.
+    Delay(u32),
+}
+
 impl Operation<'_> {
+    /// Returns the shape of the operation, which doesn't depend on the
+    /// contents of its buffer.
+    #[instability::unstable]
+    pub fn shape(&self) -> OperationShape {
+        match self {
+            Operation::Write(buffer) => OperationShape::Write(buffer.len()),
+            Operation::Read(buffer) => OperationShape::Read(buffer.len()),
+            Operation::Delay(us) => OperationShape::Delay(*us),
+        }
+    }
+
     fn is_write(&self) -> bool {
         matches!(self, Operation::Write(_))
     }
@@ -313,6 +791,7 @@ impl Operation<'_> {
         match self {
             Operation::Write(_) => OpKind::Write,
             Operation::Read(_) => OpKind::Read,
+            Operation::Delay(_) => OpKind::Delay,
         }
     }
 
@@ -320,10 +799,62 @@ impl Operation<'_> {
         match self {
             Operation::Write(buffer) => buffer.is_empty(),
             Operation::Read(buffer) => buffer.is_empty(),
+            Operation::Delay(_) => false,
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    fn len(&self) -> usize {
+        match self {
+            Operation::Write(buffer) => buffer.len(),
+            Operation::Read(buffer) => buffer.len(),
+            Operation::Delay(_) => 0,
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    fn direction(&self) -> &'static str {
+        match self {
+            Operation::Write(_) => "write",
+            Operation::Read(_) => "read",
+            Operation::Delay(_) => "delay",
         }
     }
 }
 
+/// Summary of how a transaction maps onto the commands executed by the
+/// peripheral, as returned by [`I2c::plan`].
+///
+/// The peripheral executes a transaction as one or more command sequences.
+/// Each sequence is made up of START, WRITE, READ, END and STOP commands and
+/// has to fit into the command registers of the peripheral.
+#[instability::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CommandPlan {
+    sequences: usize,
+    commands: usize,
+    longest_sequence: usize,
+}
+
+#[instability::unstable]
+impl CommandPlan {
+    /// The number of command sequences the transaction is split into.
+    pub fn sequences(&self) -> usize {
+        self.sequences
+    }
+
+    /// The total number of commands executed for the transaction.
+    pub fn commands(&self) -> usize {
+        self.commands
+    }
+
+    /// The number of commands in the longest command sequence.
+    pub fn longest_sequence(&self) -> usize {
+        self.longest_sequence
+    }
+}
+
 impl embedded_hal::i2c::Error for Error {
     fn kind(&self) -> embedded_hal::i2c::ErrorKind {
         use embedded_hal::i2c::ErrorKind;
@@ -332,13 +863,23 @@ impl embedded_hal::i2c::Error for Error {
             Self::FifoExceeded => ErrorKind::Overrun,
             Self::ArbitrationLost => ErrorKind::ArbitrationLoss,
             Self::AcknowledgeCheckFailed(reason) => ErrorKind::NoAcknowledge(reason.into()),
-            _ => ErrorKind::Other,
+            // The bus didn't behave as expected, so the peripheral couldn't finish the
+            // command sequence
+            Self::ExecutionIncomplete => ErrorKind::Bus,
+            // embedded-hal has no dedicated kind for timeouts
+            Self::Timeout | Self::ClockStretchTimeout => ErrorKind::Other,
+            // These are caused by invalid requests, not by the bus
+            Self::TransferTooLong { .. }
+            | Self::CommandNumberExceeded
+            | Self::ZeroLengthInvalid
+            | Self::ReservedAddress => ErrorKind::Other,
         }
     }
 }
 
 /// A generic I2C Command
-#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 enum Command {
     Start,
     Stop,
@@ -367,8 +908,8 @@ enum OperationType {
     Read  = 1,
 }
 
-#[derive(Eq, PartialEq, Copy, Clone)]
-#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 enum Ack {
     Ack  = 0,
     Nack = 1,
@@ -390,63 +931,492 @@ impl From<Ack> for u32 {
     }
 }
 
+/// How [`I2c::read_when_ready`] polls the status register of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+pub struct ReadyPoll {
+    /// Time between two reads of the status register.
+    pub interval: Duration,
+
+    /// How long the device may take to become ready.
+    pub timeout: Duration,
+}
+
 /// I2C driver configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, procmacros::BuilderLite)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub struct Config {
     /// The I2C clock frequency.
+    ///
+    /// Frequencies above the maximum supported by the chip are rejected with
+    /// [`ConfigError::FrequencyInvalid`].
+    #[cfg_attr(
+        any(esp32c2, esp32c3, esp32s3),
+        doc = "The maximum frequency is 800 kHz."
+    )]
+    #[cfg_attr(
+        not(any(esp32c2, esp32c3, esp32s3)),
+        doc = "The maximum frequency is 1 MHz (Fast Mode Plus)."
+    )]
+    /// High-speed mode (up to 3.4 MHz) isn't supported, as the controller can't
+    /// send the master code and switch the bus speed after it.
+    ///
+    /// Default value is 100 kHz. The default can be changed for the whole
+    /// project by setting `ESP_HAL_CONFIG_I2C_DEFAULT_FREQUENCY` to a
+    /// frequency in Hz, e.g. to `400000` if all devices on the bus support
+    /// Fast Mode.
     pub frequency: HertzU32,
 
     /// I2C SCL timeout period.
     pub timeout: BusTimeout,
-}
 
-impl core::hash::Hash for Config {
-    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        self.frequency.to_Hz().hash(state); // `HertzU32` doesn't implement `Hash`
-        self.timeout.hash(state);
-    }
-}
+    /// Upper bound for the duration of a complete async transaction.
+    ///
+    /// The hardware [`BusTimeout`] only fires while the bus is actively
+    /// stuck. If it never triggers (e.g. because SCL is held high), an async
+    /// operation could otherwise wait forever. When set, async operations that
+    /// take longer than this return [`Error::Timeout`] and the peripheral is
+    /// reset.
+    ///
+    /// The deadline is tracked with an `embassy-time` timer, so this requires
+    /// the `embassy-time` feature. Without it, configurations that set a
+    /// transaction timeout are rejected with [`ConfigError::TimeoutInvalid`].
+    ///
+    /// Default value is `None`.
+    pub transaction_timeout: Option<Duration>,
 
-impl Default for Config {
-    fn default() -> Self {
-        use fugit::RateExtU32;
-        Config {
-            frequency: 100.kHz(),
-            timeout: BusTimeout::BusCycles(10),
-        }
-    }
-}
+    /// SDA hold time, overriding the value derived from the frequency.
+    ///
+    /// The SDA hold time is the time between the falling edge of SCL and
+    /// the next change of SDA. The value is written to the SDA hold register
+    /// as-is, i.e. it is given in I2C source clock cycles.
+    ///
+    /// Values that don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub sda_hold_time: Option<u16>,
 
-/// I2C driver
-///
-/// ### I2C initialization and communication with the device
-/// ```rust, no_run
-#[doc = crate::before_snippet!()]
-/// # use esp_hal::i2c::master::{Config, I2c};
-/// # const DEVICE_ADDR: u8 = 0x77;
-/// let mut i2c = I2c::new(
-///     peripherals.I2C0,
-///     Config::default(),
-/// )
-/// .unwrap()
-/// .with_sda(peripherals.GPIO1)
-/// .with_scl(peripherals.GPIO2);
-///
-/// let mut data = [0u8; 22];
-/// i2c.write_read(DEVICE_ADDR, &[0xaa], &mut data).ok();
-/// # }
-/// ```
-#[derive(Debug)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct I2c<'d, Dm: DriverMode> {
-    i2c: PeripheralRef<'d, AnyI2c>,
-    phantom: PhantomData<Dm>,
-    config: Config,
+    /// SDA sample time, overriding the value derived from the frequency.
+    ///
+    /// The SDA sample time is the time between the rising edge of SCL and
+    /// the moment SDA is latched. The value is written to the SDA sample
+    /// register as-is, i.e. it is given in I2C source clock cycles.
+    ///
+    /// Values that don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub sda_sample_time: Option<u16>,
+
+    /// Repeated START setup time, overriding the value derived from the
+    /// frequency.
+    ///
+    /// This is the time between the rising edge of SCL and the falling edge of
+    /// SDA of a repeated START condition, in I2C source clock cycles.
+    /// Values that don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub scl_rstart_setup_time: Option<u16>,
+
+    /// STOP setup time, overriding the value derived from the frequency.
+    ///
+    /// This is the time between the rising edge of SCL and the rising edge of
+    /// SDA of a STOP condition, in I2C source clock cycles. Values that
+    /// don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub scl_stop_setup_time: Option<u16>,
+
+    /// START hold time, overriding the value derived from the frequency.
+    ///
+    /// This is the time between the falling edge of SDA of a START condition
+    /// and the falling edge of SCL, in I2C source clock cycles. Values that
+    /// don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub scl_start_hold_time: Option<u16>,
+
+    /// STOP hold time, overriding the value derived from the frequency.
+    ///
+    /// This is the time between the rising edge of SDA of a STOP condition and
+    /// the end of the transaction, in I2C source clock cycles. Values that
+    /// don't fit into the register are rejected with
+    /// [`ConfigError::TimingInvalid`].
+    ///
+    /// Default value is `None`.
+    pub scl_stop_hold_time: Option<u16>,
+
+    /// SCL wait-high period, overriding the value derived from the frequency.
+    ///
+    /// The SCL high period is split into the wait-high period, during which
+    /// the controller waits for SCL to rise, and the remaining `scl_high`
+    /// period. By default the split depends on the frequency, which doesn't
+    /// suit every bus. The value is given in I2C source clock cycles and
+    /// doesn't change the total SCL period.
+    ///
+    /// `scl_wait_high < scl_high` must hold, i.e. the value must be less than
+    /// half of the SCL high period, and it must fit into the register.
+    /// Otherwise applying the configuration fails with
+    /// [`ConfigError::FrequencyInvalid`].
+    ///
+    /// The ESP32 doesn't have a wait-high period, so the value is ignored
+    /// there.
+    ///
+    /// Default value is `None`.
+    pub scl_wait_high: Option<u16>,
+
+    /// SCL duty cycle, given as the percentage of the clock period SCL is
+    /// held low.
+    ///
+    /// By default, SCL is low for about half of the period. Buses with slow
+    /// rising edges may need a longer low period. The total period is still
+    /// determined by [`Config::frequency`].
+    ///
+    /// Duty cycles that would violate the minimum SCL low or high time
+    /// required by the I2C specification for the configured frequency are
+    /// rejected with [`ConfigError::DutyCycleInvalid`].
+    ///
+    /// Default value is `None`.
+    pub duty_cycle: Option<u8>,
+
+    /// Enables support for buses with more than one master.
+    ///
+    /// Before issuing a START, the driver waits for the bus to become idle
+    /// instead of interrupting a transaction of another master. When another
+    /// master wins the bus arbitration before the first operation of a
+    /// transaction has completed, the driver waits for the bus to become idle
+    /// and retries the operation instead of returning
+    /// [`Error::ArbitrationLost`]. If the bus doesn't become idle in time,
+    /// [`Error::Timeout`] is returned.
+    ///
+    /// Default value is `false`.
+    pub multi_master: bool,
+
+    /// Skips resetting the FIFOs and the command list before each
+    /// transaction.
+    ///
+    /// After a successful transaction the FIFOs are empty and the command list
+    /// is overwritten from the start by the next one, so resetting them isn't
+    /// strictly required. Skipping it shortens the time until the next
+    /// transaction starts on the bus and makes it more predictable. The
+    /// peripheral is still fully reset after a failed transaction.
+    ///
+    /// Default value is `false`.
+    pub low_latency: bool,
+
+    /// The order in which the bits of each byte are sent and received.
+    ///
+    /// Only a few devices need [`BitOrder::LsbFirst`]. Note that the order
+    /// applies to the address byte as well.
+    ///
+    /// Default value is [`BitOrder::MsbFirst`].
+    pub bit_order: BitOrder,
+
+    /// How read operations with an empty buffer are handled in transactions.
+    ///
+    /// Note that [`EmptyRead::Probe`] only applies to transactions that
+    /// consist of nothing but the empty read.
+    ///
+    /// Default value is [`EmptyRead::Skip`].
+    pub empty_read: EmptyRead,
+
+    /// Inverts SDA between the peripheral and its pins.
+    ///
+    /// This is meant for digital isolators that invert the signal, so that
+    /// the devices behind the isolator see the levels the peripheral
+    /// intends. The line levels reported by the driver, e.g. by
+    /// [`I2c::bus_state`], are the levels of the bus, not of the pins.
+    ///
+    /// **Only set this if the signal is actually inverted.** With a wrong
+    /// setting, the peripheral sees the released line as low, so the bus
+    /// appears to be permanently stuck and every transaction fails.
+    ///
+    /// The inversion is applied in the GPIO matrix, so it has no effect on
+    /// signals that are connected through the IO MUX.
+    ///
+    /// Default value is `false`.
+    pub invert_sda: bool,
+
+    /// Inverts SCL between the peripheral and its pins.
+    ///
+    /// See [`Self::invert_sda`] for the details, which apply to SCL as well.
+    ///
+    /// Default value is `false`.
+    pub invert_scl: bool,
+
+    /// Allows addressing the addresses reserved by the I2C specification.
+    ///
+    /// The addresses `0x00..=0x07` and `0x78..=0x7F` have special meanings,
+    /// e.g. `0x00` is the general call address, and values above `0x7F` are
+    /// not 7-bit addresses at all. They are most often the result of a typo or
+    /// of using a left-aligned address that includes the R/W bit. Unless this
+    /// is set, operations on them fail with [`Error::ReservedAddress`] before
+    /// anything is sent on the bus.
+    ///
+    /// [`I2c::send_raw_address`] sends its byte as-is and isn't affected.
+    ///
+    /// Default value is `false`.
+    pub allow_reserved_addresses: bool,
+
+    /// Leaves the bus held at the end of each transaction, so that the next
+    /// transaction to the same device continues with a repeated START.
+    ///
+    /// A `write` followed by a `read` to the same device then behaves like a
+    /// single `write_read`, without the STOP and START in between. This saves
+    /// time in tight polling loops, and no other master can take the bus
+    /// between the transactions. If the next transaction is to a different
+    /// device, the driver sends a STOP condition first.
+    ///
+    /// **The bus stays held after the last transaction.** Release it with
+    /// [`I2c::send_stop`] when done, as other masters and the devices on the
+    /// bus wait for the STOP condition. [`I2c::is_bus_held`] tells whether
+    /// this is needed.
+    ///
+    /// Default value is `false`.
+    pub coalesce_transactions: bool,
+}
+
+impl core::hash::Hash for Config {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.frequency.to_Hz().hash(state); // `HertzU32` doesn't implement `Hash`
+        self.timeout.hash(state);
+        self.transaction_timeout.map(|t| t.to_micros()).hash(state);
+        self.sda_hold_time.hash(state);
+        self.sda_sample_time.hash(state);
+        self.scl_rstart_setup_time.hash(state);
+        self.scl_stop_setup_time.hash(state);
+        self.scl_start_hold_time.hash(state);
+        self.scl_stop_hold_time.hash(state);
+        self.scl_wait_high.hash(state);
+        self.duty_cycle.hash(state);
+        self.multi_master.hash(state);
+        self.low_latency.hash(state);
+        self.bit_order.hash(state);
+        self.empty_read.hash(state);
+        self.invert_sda.hash(state);
+        self.invert_scl.hash(state);
+        self.allow_reserved_addresses.hash(state);
+        self.coalesce_transactions.hash(state);
+    }
+}
+
+impl Config {
+    /// Checks whether the configuration can be applied, without touching the
+    /// peripheral.
+    ///
+    /// This performs the same frequency and timing checks as
+    /// [`I2c::apply_config`], for a peripheral clocked by `clock`. On the
+    /// ESP32 and ESP32-S2 this is the APB clock, on other chips it is the
+    /// crystal clock.
+    pub fn validate(&self, clock: HertzU32) -> Result<(), ConfigError> {
+        self.check_transaction_timeout()?;
+        Driver::clock_timings(clock, self).map(|_| ())
+    }
+
+    /// Rejects a transaction timeout if there is no timer to wait for it.
+    fn check_transaction_timeout(&self) -> Result<(), ConfigError> {
+        if cfg!(not(feature = "embassy-time")) && self.transaction_timeout.is_some() {
+            return Err(ConfigError::TimeoutInvalid);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        use fugit::RateExtU32;
+        Config {
+            frequency: DEFAULT_FREQUENCY.Hz(),
+            timeout: BusTimeout::BusCycles(10),
+            transaction_timeout: None,
+            sda_hold_time: None,
+            sda_sample_time: None,
+            scl_rstart_setup_time: None,
+            scl_stop_setup_time: None,
+            scl_start_hold_time: None,
+            scl_stop_hold_time: None,
+            scl_wait_high: None,
+            duty_cycle: None,
+            multi_master: false,
+            low_latency: false,
+            bit_order: BitOrder::MsbFirst,
+            empty_read: EmptyRead::Skip,
+            invert_sda: false,
+            invert_scl: false,
+            allow_reserved_addresses: false,
+            coalesce_transactions: false,
+        }
+    }
+}
+
+/// The bus timings programmed into the peripheral, as returned by
+/// [`I2c::dump_timing`].
+///
+/// The values are read back from the timing registers, in cycles of the
+/// peripheral's source clock after the divider. On the ESP32 and ESP32-S2 the
+/// source clock is the APB clock, on other chips it is the crystal clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[instability::unstable]
+#[non_exhaustive]
+pub struct TimingRegisters {
+    /// The divider applied to the source clock. Always 1 on the ESP32 and
+    /// ESP32-S2, which don't have a divider.
+    pub clock_divider: u32,
+
+    /// The low period of SCL.
+    pub scl_low_period: u32,
+
+    /// The high period of SCL.
+    pub scl_high_period: u32,
+
+    /// How long the peripheral waits after releasing SCL before it samples
+    /// the line. Always 0 on the ESP32.
+    pub scl_wait_high_period: u32,
+
+    /// The hold time of SDA after the falling edge of SCL.
+    pub sda_hold_time: u32,
+
+    /// The sample time of SDA after the rising edge of SCL.
+    pub sda_sample_time: u32,
+
+    /// The setup time of a repeated START condition.
+    pub scl_rstart_setup_time: u32,
+
+    /// The setup time of a STOP condition.
+    pub scl_stop_setup_time: u32,
+
+    /// The hold time of a START condition.
+    pub scl_start_hold_time: u32,
+
+    /// The hold time of a STOP condition.
+    pub scl_stop_hold_time: u32,
+
+    /// The raw value of the bus timeout register, or `None` if the timeout is
+    /// disabled.
+    ///
+    /// On the ESP32 and ESP32-S2 this is a number of clock cycles, on other
+    /// chips it is the exponent of a power of two.
+    pub timeout: Option<u32>,
+}
+
+/// I2C driver
+///
+/// ### I2C initialization and communication with the device
+/// ```rust, no_run
+#[doc = crate::before_snippet!()]
+/// # use esp_hal::i2c::master::{Config, I2c};
+/// # const DEVICE_ADDR: u8 = 0x77;
+/// let mut i2c = I2c::new(
+///     peripherals.I2C0,
+///     Config::default(),
+/// )
+/// .unwrap()
+/// .with_sda(peripherals.GPIO1)
+/// .with_scl(peripherals.GPIO2);
+///
+/// let mut data = [0u8; 22];
+/// i2c.write_read(DEVICE_ADDR, &[0xaa], &mut data).ok();
+/// # }
+/// ```
+///
+/// ### Moving the driver to another core
+///
+/// A blocking driver is `Send` and can be created on one core and used on
+/// another. Interrupt handlers are bound to the core that registers them, so
+/// if the blocking driver uses interrupts, the handler needs to be registered
+/// again on the new core by calling [`I2c::set_interrupt_handler`].
+///
+/// An async driver is not `Send`. [`I2c::into_async`] binds the interrupt
+/// handler that wakes its futures to the current core, so after a move the
+/// completion interrupts would still be delivered to the original core. To
+/// use the driver on another core, move a blocking driver there and convert
+/// it with [`I2c::into_async`] on that core.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct I2c<'d, Dm: DriverMode> {
+    i2c: PeripheralRef<'d, AnyI2c>,
+    phantom: PhantomData<Dm>,
+    config: Config,
+    // Must be dropped before `guard` and the pins, so that the STOP condition
+    // can still be sent
+    bus_release: BusRelease,
     guard: PeripheralGuard,
     sda_pin: PinGuard,
     scl_pin: PinGuard,
+    pin_record: PinRecord,
+    scl_push_pull: bool,
+    // The core on which the handler that wakes blocking operations is bound
+    wait_for_interrupt: Option<Cpu>,
+    user_handler: bool,
+}
+
+/// Sends a STOP condition when the driver is dropped while it holds the bus.
+///
+/// The bus is left held if the driver is dropped, e.g. together with a
+/// cancelled future, after a command sequence that ended with an END command
+/// instead of a STOP. This is a field of [`I2c`] instead of a `Drop`
+/// implementation of the driver, so that the driver can still be taken
+/// apart when changing its mode.
+#[derive(Debug)]
+struct BusRelease {
+    info: &'static Info,
+    // With multiple masters, a busy bus may be held by another master
+    multi_master: bool,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for BusRelease {
+    fn format(&self, fmt: defmt::Formatter<'_>) {
+        defmt::write!(fmt, "BusRelease {{ multi_master: {} }}", self.multi_master);
+    }
+}
+
+impl Drop for BusRelease {
+    fn drop(&mut self) {
+        if !self.multi_master {
+            self.info.release_bus();
+        }
+    }
+}
+
+/// Clears the pins recorded in [`State`] when the driver is dropped, so that
+/// they can be used by another I2C peripheral.
+struct PinRecord {
+    state: &'static State,
+}
+
+impl core::fmt::Debug for PinRecord {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinRecord").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for PinRecord {
+    fn format(&self, fmt: defmt::Formatter<'_>) {
+        defmt::write!(fmt, "PinRecord")
+    }
+}
+
+impl PinRecord {
+    fn new(state: &'static State) -> Self {
+        state.clear_pins();
+        Self { state }
+    }
+}
+
+impl Drop for PinRecord {
+    fn drop(&mut self) {
+        self.state.clear_pins();
+    }
 }
 
 #[cfg(any(doc, feature = "unstable"))]
@@ -483,107 +1453,818 @@ impl<'d, Dm: DriverMode> I2c<'d, Dm> {
         Driver {
             info: self.i2c.info(),
             state: self.i2c.state(),
+            config: &self.config,
+            scl_push_pull: self.scl_push_pull,
+            wait_for_interrupt: self.wait_for_interrupt == Some(Cpu::current()),
+            check_data_ack: true,
         }
     }
 
     fn internal_recover(&self) {
+        let state = self.driver().state;
+        state.last_error_address.store(
+            state.current_address.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        state.bus_held.store(false, Ordering::Relaxed);
+
+        // We know the configuration is valid, we can ignore the result.
+        _ = self.reinit();
+    }
+
+    fn reinit(&self) -> Result<(), ConfigError> {
         PeripheralClockControl::disable(self.driver().info.peripheral);
         PeripheralClockControl::enable(self.driver().info.peripheral);
         PeripheralClockControl::reset(self.driver().info.peripheral);
 
-        // We know the configuration is valid, we can ignore the result.
-        _ = self.driver().setup(&self.config);
+        self.driver().setup(&self.config)
+    }
+
+    /// Resets the peripheral and sets it up again with the current
+    /// configuration.
+    ///
+    /// The driver already does this after a failed transaction. This function
+    /// can be used to recover the peripheral when a problem was detected by
+    /// other means, e.g. a device that stopped responding.
+    #[instability::unstable]
+    pub fn reset(&mut self) -> Result<(), ConfigError> {
+        self.reinit()
+    }
+
+    /// Discards the contents of the TX and RX FIFOs and clears pending
+    /// interrupts.
+    ///
+    /// This can be used to make sure no stale data is left in the FIFOs before
+    /// retrying after a protocol error, without reinitializing the peripheral
+    /// like [`Self::reset`] does. Note that this doesn't issue a STOP
+    /// condition.
+    #[instability::unstable]
+    pub fn flush(&mut self) {
+        let driver = self.driver();
+        driver.reset_fifo();
+        driver.clear_all_interrupts();
     }
 
     /// Applies a new configuration.
     pub fn apply_config(&mut self, config: &Config) -> Result<(), ConfigError> {
         self.driver().setup(config)?;
         self.config = *config;
+        self.bus_release.multi_master = config.multi_master;
+        self.apply_line_inversion();
         Ok(())
     }
 
+    /// Changes the bus frequency, keeping the rest of the configuration.
+    ///
+    /// This is a shorthand for [`Self::apply_config`] with a copy of the
+    /// current configuration where only the frequency is changed.
+    #[instability::unstable]
+    pub fn set_frequency(&mut self, frequency: HertzU32) -> Result<(), ConfigError> {
+        let config = self.config.with_frequency(frequency);
+        self.apply_config(&config)
+    }
+
+    /// Changes the bus timeout, keeping the rest of the configuration.
+    ///
+    /// This is a shorthand for [`Self::apply_config`] with a copy of the
+    /// current configuration where only the timeout is changed.
+    #[instability::unstable]
+    pub fn set_timeout(&mut self, timeout: BusTimeout) -> Result<(), ConfigError> {
+        let config = self.config.with_timeout(timeout);
+        self.apply_config(&config)
+    }
+
+    /// Returns the currently applied configuration.
+    #[instability::unstable]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the currently configured bus frequency.
+    #[instability::unstable]
+    pub fn frequency(&self) -> HertzU32 {
+        self.config.frequency
+    }
+
+    /// Returns the currently configured bus timeout.
+    #[instability::unstable]
+    pub fn timeout(&self) -> BusTimeout {
+        self.config.timeout
+    }
+
+    /// Reads back the bus timings programmed into the peripheral.
+    ///
+    /// This shows the values the driver computed from the [`Config`], which
+    /// helps to find out why a bus runs at an unexpected frequency. The result
+    /// can be printed with `Debug` or `defmt`.
+    ///
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// let i2c = I2c::new(peripherals.I2C0, Config::default()).unwrap();
+    ///
+    /// let timing = i2c.dump_timing();
+    /// let scl_period = timing.scl_low_period + timing.scl_high_period;
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn dump_timing(&self) -> TimingRegisters {
+        self.driver().dump_timing()
+    }
+
     fn transaction_impl<'a>(
         &mut self,
         address: I2cAddress,
         operations: impl Iterator<Item = Operation<'a>>,
+    ) -> Result<(), Error> {
+        self.transaction_impl_tracked(address, operations, &mut Progress::default())
+    }
+
+    /// Executes a transaction, holding the bus at the end if
+    /// [`Config::coalesce_transactions`] is set.
+    fn transaction_impl_tracked<'a>(
+        &mut self,
+        address: I2cAddress,
+        operations: impl Iterator<Item = Operation<'a>>,
+        progress: &mut Progress,
+    ) -> Result<(), Error> {
+        let coalesce = self.config.coalesce_transactions;
+        if coalesce && self.driver().is_held_for_other_device(address) {
+            self.driver().send_stop_blocking()?;
+        }
+        self.run_transaction(address, operations, !coalesce, progress)
+    }
+
+    /// Executes the operations of a transaction. If `stop` is false, the
+    /// last operation ends with END instead of STOP, and the bus is left held.
+    ///
+    /// `progress` is kept up to date with the operation being executed, so
+    /// that it identifies the failed operation if an error is returned.
+    fn run_transaction<'a>(
+        &mut self,
+        address: I2cAddress,
+        operations: impl Iterator<Item = Operation<'a>>,
+        stop: bool,
+        progress: &mut Progress,
     ) -> Result<(), Error> {
         let mut last_op: Option<OpKind> = None;
-        // filter out 0 length read operations
+        let empty_read = self.config.empty_read;
+        // filter out 0 length read operations, unless configured otherwise
         let mut op_iter = operations
-            .filter(|op| op.is_write() || !op.is_empty())
+            .enumerate()
+            .filter(|(_, op)| op.is_write() || !op.is_empty() || empty_read != EmptyRead::Skip)
             .peekable();
 
-        while let Some(op) = op_iter.next() {
-            let next_op = op_iter.peek().map(|v| v.kind());
+        while let Some((index, mut op)) = op_iter.next() {
+            let next_op = op_iter.peek().map(|(_, v)| v.kind());
             let kind = op.kind();
-            match op {
-                Operation::Write(buffer) => {
-                    // execute a write operation:
-                    // - issue START/RSTART if op is different from previous
-                    // - issue STOP if op is the last one
-                    self.driver().write_blocking(
-                        address,
-                        buffer,
-                        !matches!(last_op, Some(OpKind::Write)),
-                        next_op.is_none(),
-                    )?;
+            *progress = Progress {
+                operation: index,
+                transferred: 0,
+            };
+
+            if kind == OpKind::Read && op.is_empty() {
+                if empty_read == EmptyRead::Probe && last_op.is_none() && next_op.is_none() {
+                    return self.driver().smbus_quick_blocking(address, true);
                 }
-                Operation::Read(buffer) => {
-                    // execute a read operation:
-                    // - issue START/RSTART if op is different from previous
-                    // - issue STOP if op is the last one
-                    // - will_continue is true if there is another read operation next
-                    self.driver().read_blocking(
+                return Err(Error::ZeroLengthInvalid);
+            }
+
+            // A new START follows, make sure no other master owns the bus
+            if matches!(last_op, None | Some(OpKind::Delay)) && kind != OpKind::Delay {
+                self.driver().begin_transaction_blocking()?;
+            }
+
+            let mut retries = 0;
+            loop {
+                #[cfg(feature = "trace")]
+                trace!(
+                    "I2C {} to {:?}: {} bytes",
+                    op.direction(),
+                    address,
+                    op.len()
+                );
+
+                let result = match &mut op {
+                    Operation::Write(buffer) => {
+                        // execute a write operation:
+                        // - issue START/RSTART if op is different from previous
+                        // - issue STOP if op is the last one (unless the bus is to be held) or
+                        //   followed by a delay
+                        self.driver().write_blocking_counted(
+                            address,
+                            buffer,
+                            !matches!(last_op, Some(OpKind::Write)),
+                            ends_with_stop(next_op, stop),
+                            &mut progress.transferred,
+                        )
+                    }
+                    Operation::Read(buffer) => {
+                        // execute a read operation:
+                        // - issue START/RSTART if op is different from previous
+                        // - issue STOP if op is the last one (unless the bus is to be held) or
+                        //   followed by a delay
+                        // - will_continue is true if there is another read operation next
+                        self.driver().read_blocking_counted(
+                            address,
+                            buffer,
+                            !matches!(last_op, Some(OpKind::Read)),
+                            ends_with_stop(next_op, stop),
+                            matches!(next_op, Some(OpKind::Read)),
+                            &mut progress.transferred,
+                        )
+                    }
+                    Operation::Delay(us) => {
+                        crate::delay::Delay::new().delay_micros(*us);
+                        Ok(())
+                    }
+                };
+
+                #[cfg(feature = "trace")]
+                match &result {
+                    Ok(()) => trace!("I2C {} to {:?} done", op.direction(), address),
+                    Err(error) => trace!(
+                        "I2C {} to {:?} failed: {:?}",
+                        op.direction(),
                         address,
-                        buffer,
-                        !matches!(last_op, Some(OpKind::Read)),
-                        next_op.is_none(),
-                        matches!(next_op, Some(OpKind::Read)),
-                    )?;
+                        error
+                    ),
+                }
+
+                match result {
+                    // In multi-master mode, back off and retry if another master won
+                    // the arbitration before we got to own the bus. The retry starts
+                    // over as a new transaction, discarding what the lost attempt left
+                    // in the FIFOs.
+                    Err(Error::ArbitrationLost)
+                        if self.config.multi_master
+                            && matches!(last_op, None | Some(OpKind::Delay))
+                            && retries < MAX_ARBITRATION_RETRIES =>
+                    {
+                        retries += 1;
+                        progress.transferred = 0;
+                        self.driver().begin_transaction_blocking()?;
+                    }
+                    result => break result?,
                 }
             }
 
             last_op = Some(kind);
         }
 
+        if !stop && matches!(last_op, Some(OpKind::Write | OpKind::Read)) {
+            self.driver().state.bus_held.store(true, Ordering::Relaxed);
+        }
+
         Ok(())
     }
 
-    /// Connect a pin to the I2C SDA signal.
+    /// Returns the peripheral data and state of this instance.
     ///
-    /// This will replace previous pin assignments for this signal.
-    pub fn with_sda(mut self, sda: impl Peripheral<P = impl PeripheralOutput> + 'd) -> Self {
-        let info = self.driver().info;
-        let input = info.sda_input;
-        let output = info.sda_output;
-        Self::connect_pin(sda, input, output, &mut self.sda_pin);
-
-        self
+    /// This is meant for applications that register their own interrupt
+    /// handler, for example when using RTIC. Obtain the parts once during
+    /// initialization and pass them to [`handle_interrupt`] from the handler
+    /// bound to the I2C interrupt, to drive the asynchronous operations of
+    /// this driver.
+    #[instability::unstable]
+    pub fn interrupt_parts(&self) -> (&'static Info, &'static State) {
+        self.i2c.parts()
     }
 
-    /// Connect a pin to the I2C SCL signal.
+    /// Returns the number of command registers of the peripheral.
     ///
-    /// This will replace previous pin assignments for this signal.
-    pub fn with_scl(mut self, scl: impl Peripheral<P = impl PeripheralOutput> + 'd) -> Self {
-        let info = self.driver().info;
-        let input = info.scl_input;
-        let output = info.scl_output;
-        Self::connect_pin(scl, input, output, &mut self.scl_pin);
+    /// Each command sequence of a transaction has to fit into the command
+    /// registers, see [`Self::plan`]. The number depends on the chip, so
+    /// device drivers that need to run on every chip should keep their
+    /// sequences within the smallest number.
+    #[instability::unstable]
+    pub fn command_slots(&self) -> usize {
+        self.driver().regs().comd_iter().count()
+    }
+
+    /// Computes how the given operations would be executed by the peripheral,
+    /// without executing them.
+    ///
+    /// This follows the same rules as [`I2c::transaction`] and returns
+    /// [`Error::CommandNumberExceeded`] if a command sequence doesn't fit into
+    /// the command registers of the peripheral. It can be used to validate
+    /// the shape of a transaction before running it.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c, Operation};
+    /// # let i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// let mut data = [0u8; 22];
+    /// let ops = [Operation::Write(&[0xaa]), Operation::Read(&mut data)];
+    /// assert!(i2c.plan(&ops).is_ok());
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn plan<'a>(
+        &self,
+        operations: impl IntoIterator<Item = &'a Operation<'a>>,
+    ) -> Result<CommandPlan, Error> {
+        let capacity = self.command_slots();
+        let mut plan = CommandPlan {
+            sequences: 0,
+            commands: 0,
+            longest_sequence: 0,
+        };
+
+        let mut add_sequence = |commands: usize| {
+            if commands > capacity {
+                return Err(Error::CommandNumberExceeded);
+            }
+            if commands > 0 {
+                plan.sequences += 1;
+                plan.commands += commands;
+                plan.longest_sequence = plan.longest_sequence.max(commands);
+            }
+            Ok(())
+        };
+
+        // This mirrors `transaction_impl` and the command setup in
+        // `start_write_operation` and `start_read_operation`.
+        let mut last_op: Option<OpKind> = None;
+        let empty_read = self.config.empty_read;
+        let mut op_iter = operations
+            .into_iter()
+            .filter(|op| op.is_write() || !op.is_empty() || empty_read != EmptyRead::Skip)
+            .peekable();
+
+        while let Some(op) = op_iter.next() {
+            let next_op = op_iter.peek().map(|v| v.kind());
+            let stop = matches!(next_op, None | Some(OpKind::Delay));
+
+            match op {
+                Operation::Read(buffer) if buffer.is_empty() => {
+                    if empty_read == EmptyRead::Probe && last_op.is_none() && next_op.is_none() {
+                        // START, WRITE (address), STOP
+                        add_sequence(3)?;
+                    } else {
+                        return Err(Error::ZeroLengthInvalid);
+                    }
+                }
+                Operation::Write(buffer) => {
+                    let start = !matches!(last_op, Some(OpKind::Write));
+                    if buffer.is_empty() {
+                        if start || stop {
+                            // START, WRITE (address only), STOP/END
+                            add_sequence(usize::from(start) * 2 + 1)?;
+                        }
+                    } else {
+                        for chunk in write_chunks(buffer.len(), start) {
+                            let start = start && chunk.start == 0;
+                            // [START], WRITE, STOP/END
+                            add_sequence(usize::from(start) + 2)?;
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    let start = !matches!(last_op, Some(OpKind::Read));
+                    let will_continue = matches!(next_op, Some(OpKind::Read));
+                    for chunk in read_chunks(buffer.len(), will_continue) {
+                        let start = start && chunk.start == 0;
+                        let will_continue = will_continue || chunk.end < buffer.len();
+                        let initial_len = if will_continue {
+                            chunk.len()
+                        } else {
+                            chunk.len() - 1
+                        };
+                        // [START, WRITE (address)], [READ], [READ (NACK)], STOP/END
+                        add_sequence(
+                            usize::from(start) * 2
+                                + usize::from(initial_len > 0)
+                                + usize::from(!will_continue)
+                                + 1,
+                        )?;
+                    }
+                }
+                Operation::Delay(_) => {}
+            }
+
+            last_op = Some(op.kind());
+        }
+
+        Ok(plan)
+    }
+
+    /// Returns the address of the device the driver was talking to when the
+    /// last transaction on the bus failed.
+    ///
+    /// This can be used to find out which device caused an error in code that
+    /// talks to several devices. Returns `None` if no transaction has failed
+    /// yet. Errors reported before anything was sent to the bus, e.g.
+    /// [`Error::ZeroLengthInvalid`], don't change the address.
+    #[instability::unstable]
+    pub fn last_error_address(&self) -> Option<I2cAddress> {
+        match self
+            .driver()
+            .state
+            .last_error_address
+            .load(Ordering::Relaxed)
+        {
+            NO_ADDRESS => None,
+            addr => Some(I2cAddress::SevenBit(addr)),
+        }
+    }
+
+    /// Returns the error flags the peripheral reported when the last
+    /// transaction failed.
+    ///
+    /// The driver resets the peripheral after a failure, which clears the
+    /// flags, so they are saved as soon as the error is detected. This allows
+    /// inspecting the cause of an error in more detail than the returned
+    /// [`Error`], e.g. to decide how to recover.
+    ///
+    /// The flags are cleared when a transaction starts, so they are all
+    /// `false` after a successful transaction and after an error detected by a
+    /// software timeout. Errors reported before anything was sent to the bus,
+    /// e.g. [`Error::ZeroLengthInvalid`], don't change the flags.
+    #[instability::unstable]
+    pub fn error_flags(&self) -> BusErrorFlags {
+        BusErrorFlags::from_bits(self.driver().state.last_error_flags.load(Ordering::Relaxed))
+    }
+
+    /// Returns when the interrupt handler last saw a command sequence
+    /// complete.
+    ///
+    /// The timestamp is taken in the interrupt handler as soon as the
+    /// peripheral reports that a transaction ended with a STOP condition, or
+    /// that a part of it ended with an END command. Unlike a timestamp taken
+    /// after awaiting the transaction, it isn't delayed by the scheduling of
+    /// the awaiting task, only by the interrupt latency. This makes it useful
+    /// for correlating reads with a timeline, e.g. when sampling a sensor.
+    ///
+    /// Completions are only recorded when they are signalled by the
+    /// interrupt, i.e. by async drivers and by blocking drivers that wait
+    /// for interrupts (see [`I2c::set_wait_for_interrupt`]). Returns `None`
+    /// if no completion has been recorded yet.
+    #[instability::unstable]
+    pub fn last_completion_timestamp(&self) -> Option<Instant> {
+        self.driver().state.last_completion.with(|t| *t)
+    }
+
+    /// Returns how often another master won the arbitration for the bus.
+    ///
+    /// The counter belongs to the peripheral, so it keeps counting across
+    /// driver instances. It includes losses that were resolved by retrying in
+    /// [`Config::multi_master`] mode, which makes it a measure of the
+    /// contention on the bus. The counter wraps around on overflow.
+    #[instability::unstable]
+    pub fn arbitration_loss_count(&self) -> u32 {
+        self.driver()
+            .state
+            .arbitration_losses
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the current state of the bus.
+    ///
+    /// The line levels are read from the pins connected to SDA and SCL. This
+    /// is useful for monitoring the bus health, e.g. to find out whether a
+    /// device is holding one of the lines low.
+    #[instability::unstable]
+    pub fn bus_state(&self) -> BusState {
+        if self.driver().is_bus_busy() {
+            return BusState::Busy;
+        }
+
+        let sda_high = self
+            .sda_pin
+            .is_input_high()
+            .map(|high| high != self.config.invert_sda);
+        let scl_high = self
+            .scl_pin
+            .is_input_high()
+            .map(|high| high != self.config.invert_scl);
+
+        match (sda_high, scl_high) {
+            (_, Some(false)) => BusState::SclStuckLow,
+            (Some(false), _) => BusState::SdaStuckLow,
+            _ => BusState::Idle,
+        }
+    }
+
+    /// Checks that SDA and SCL are pulled up and can be driven low.
+    ///
+    /// Without pull-ups the lines float, and every transaction fails with a
+    /// timeout that doesn't point at the cause. This check releases each line
+    /// and verifies that it reads high, then drives it low and verifies that
+    /// it reads low, and finally verifies that it reads high again after it
+    /// has been released. Lines without a pin, and SCL in push-pull mode, are
+    /// not checked.
+    ///
+    /// The check is meant to be run once, before the first transaction.
+    /// Driving SDA low and releasing it while SCL is high looks like a START
+    /// condition followed by a STOP condition to the devices on the bus,
+    /// which they ignore. It must not be run while another master may be
+    /// using the bus.
+    ///
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// let i2c = I2c::new(peripherals.I2C0, Config::default())
+    ///     .unwrap()
+    ///     .with_sda(peripherals.GPIO1)
+    ///     .with_scl(peripherals.GPIO2);
+    ///
+    /// i2c.check_bus().expect("I2C bus fault");
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn check_bus(&self) -> Result<(), BusCheckError> {
+        let info = self.driver().info;
+
+        // SCL first: pulsing SCL while SDA is high is ignored by the devices.
+        let scl = if self.scl_push_pull {
+            None
+        } else {
+            check_line(&self.scl_pin, info.scl_output, self.config.invert_scl)
+        };
+        let sda = check_line(&self.sda_pin, info.sda_output, self.config.invert_sda);
+
+        if sda.is_none() && scl.is_none() {
+            Ok(())
+        } else {
+            Err(BusCheckError { sda, scl })
+        }
+    }
+
+    /// Returns whether a transaction is in progress on the bus.
+    ///
+    /// The bus is busy from a START condition until the following STOP
+    /// condition, no matter which master issued them. This allows checking
+    /// whether the bus is free before starting a transaction on a bus with
+    /// multiple masters.
+    #[instability::unstable]
+    pub fn is_bus_busy(&self) -> bool {
+        self.driver().is_bus_busy()
+    }
+
+    /// Returns whether the last transaction left the bus held.
+    ///
+    /// This is the case after [`I2c::transaction_no_stop`], and after any
+    /// transaction if [`Config::coalesce_transactions`] is set. The next
+    /// transaction continues with a repeated START, or [`I2c::send_stop`]
+    /// releases the bus.
+    #[instability::unstable]
+    pub fn is_bus_held(&self) -> bool {
+        self.driver().state.bus_held.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes in the TX FIFO that haven't been sent yet.
+    ///
+    /// The FIFO holds 32 bytes, so the space left for writing is 32 minus
+    /// this number. This is meant for code that fills the FIFO itself, and
+    /// wants to know the state of the FIFO without relying on the watermark
+    /// interrupts.
+    #[instability::unstable]
+    pub fn tx_fifo_count(&self) -> usize {
+        self.driver().regs().sr().read().txfifo_cnt().bits() as usize
+    }
+
+    /// Returns the number of received bytes in the RX FIFO that haven't been
+    /// read yet.
+    #[instability::unstable]
+    pub fn rx_fifo_count(&self) -> usize {
+        self.driver().regs().sr().read().rxfifo_cnt().bits() as usize
+    }
+
+    /// Connect a pin to the I2C SDA signal.
+    ///
+    /// This will replace previous pin assignments for this signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pin is already connected to the SCL signal, or to another
+    /// I2C peripheral.
+    pub fn with_sda(mut self, sda: impl Peripheral<P = impl PeripheralOutput> + 'd) -> Self {
+        let info = self.driver().info;
+        let input = info.sda_input;
+        let output = info.sda_output;
+        Self::connect_pin(sda, input, output, false, &mut self.sda_pin);
+        let pin = self.sda_pin.pin_number();
+        self.record_pins(&self.driver().state.sda_pins, pin, pin);
+        self.apply_line_inversion();
+
+        self
+    }
+
+    /// Connect separate pins to the input and the output of the I2C SDA
+    /// signal.
+    ///
+    /// This is meant for boards that route the two directions of the signal
+    /// separately, e.g. through a level shifter or an isolator. The output pin
+    /// is driven as an open drain output. Use [`Self::with_sda`] if both
+    /// directions share a pin.
+    ///
+    /// This will replace previous pin assignments for this signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pin is already connected to the SCL signal, or to another
+    /// I2C peripheral.
+    #[instability::unstable]
+    pub fn with_sda_split(
+        mut self,
+        sda_in: impl Peripheral<P = impl PeripheralInput> + 'd,
+        sda_out: impl Peripheral<P = impl PeripheralOutput> + 'd,
+    ) -> Self {
+        let info = self.driver().info;
+        let input = info.sda_input;
+        let output = info.sda_output;
+        let input_pin = Self::connect_split_pins(sda_in, sda_out, input, output, &mut self.sda_pin);
+        let output_pin = self.sda_pin.pin_number();
+        self.record_pins(&self.driver().state.sda_pins, output_pin, input_pin);
+        self.apply_line_inversion();
+
+        self
+    }
+
+    /// Connect a pin to the I2C SCL signal.
+    ///
+    /// This will replace previous pin assignments for this signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pin is already connected to the SDA signal, or to another
+    /// I2C peripheral.
+    pub fn with_scl(mut self, scl: impl Peripheral<P = impl PeripheralOutput> + 'd) -> Self {
+        self.connect_scl(scl, false);
+        self
+    }
+
+    /// Connect a pin to the I2C SCL signal, driving it as a push-pull output.
+    ///
+    /// Driving SCL actively gives sharper edges, which can allow higher
+    /// frequencies on a point-to-point link. SDA is still driven as an open
+    /// drain output.
+    ///
+    /// **Only use this if the bus has exactly one master and one slave
+    /// device.** A device stretching the clock, or another master driving SCL,
+    /// will short-circuit against the pin, which can damage the hardware.
+    ///
+    /// This will replace previous pin assignments for this signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pin is already connected to the SDA signal, or to another
+    /// I2C peripheral.
+    #[instability::unstable]
+    pub fn with_scl_push_pull(
+        mut self,
+        scl: impl Peripheral<P = impl PeripheralOutput> + 'd,
+    ) -> Self {
+        self.connect_scl(scl, true);
+        self
+    }
+
+    /// Connect separate pins to the input and the output of the I2C SCL
+    /// signal.
+    ///
+    /// This is meant for boards that route the two directions of the signal
+    /// separately, e.g. through a level shifter or an isolator. The output pin
+    /// is driven as an open drain output. Use [`Self::with_scl`] if both
+    /// directions share a pin.
+    ///
+    /// This will replace previous pin assignments for this signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pin is already connected to the SDA signal, or to another
+    /// I2C peripheral.
+    #[instability::unstable]
+    pub fn with_scl_split(
+        mut self,
+        scl_in: impl Peripheral<P = impl PeripheralInput> + 'd,
+        scl_out: impl Peripheral<P = impl PeripheralOutput> + 'd,
+    ) -> Self {
+        let info = self.driver().info;
+        let input = info.scl_input;
+        let output = info.scl_output;
+        let input_pin = Self::connect_split_pins(scl_in, scl_out, input, output, &mut self.scl_pin);
+        let output_pin = self.scl_pin.pin_number();
+        self.record_pins(&self.driver().state.scl_pins, output_pin, input_pin);
+        self.apply_line_inversion();
+
+        // The output pin doesn't necessarily reflect the level of the bus
+        self.driver().state.scl_pin.store(NO_PIN, Ordering::Relaxed);
+
+        self.scl_push_pull = false;
+        self.driver()
+            .regs()
+            .ctr()
+            .modify(|_, w| w.scl_force_out().set_bit());
 
         self
     }
 
+    fn connect_scl(
+        &mut self,
+        scl: impl Peripheral<P = impl PeripheralOutput> + 'd,
+        push_pull: bool,
+    ) {
+        let info = self.driver().info;
+        let input = info.scl_input;
+        let output = info.scl_output;
+        Self::connect_pin(scl, input, output, push_pull, &mut self.scl_pin);
+        let pin = self.scl_pin.pin_number();
+        self.record_pins(&self.driver().state.scl_pins, pin, pin);
+        self.apply_line_inversion();
+
+        // A push-pull pin can't be held low by a slave
+        let scl_pin = if push_pull {
+            NO_PIN
+        } else {
+            self.scl_pin.pin_number().unwrap_or(NO_PIN)
+        };
+        self.driver()
+            .state
+            .scl_pin
+            .store(scl_pin, Ordering::Relaxed);
+
+        self.scl_push_pull = push_pull;
+        self.driver()
+            .regs()
+            .ctr()
+            .modify(|_, w| w.scl_force_out().bit(!push_pull));
+    }
+
+    /// Applies [`Config::invert_sda`] and [`Config::invert_scl`] to the
+    /// connected lines.
+    ///
+    /// Lines without a pin are left alone, so that the idle level of their
+    /// unconnected input isn't inverted.
+    fn apply_line_inversion(&self) {
+        let driver = self.driver();
+        let info = driver.info;
+
+        if let Some(pin) = self.sda_pin.pin_number() {
+            set_input_signal_inverted(info.sda_input, self.config.invert_sda);
+            set_output_signal_inverted(pin, self.config.invert_sda);
+        }
+        if let Some(pin) = self.scl_pin.pin_number() {
+            set_input_signal_inverted(info.scl_input, self.config.invert_scl);
+            set_output_signal_inverted(pin, self.config.invert_scl);
+        }
+
+        driver
+            .state
+            .scl_inverted
+            .store(self.config.invert_scl, Ordering::Relaxed);
+    }
+
+    /// Records the output and input pin of a signal in `pins`, and checks that
+    /// they aren't used by the other signal or by another I2C peripheral.
+    fn record_pins(&self, pins: &[AtomicU8; 2], output: Option<u8>, input: Option<u8>) {
+        pins[0].store(output.unwrap_or(NO_PIN), Ordering::Relaxed);
+        pins[1].store(input.unwrap_or(NO_PIN), Ordering::Relaxed);
+
+        let state = self.driver().state;
+        for pin in recorded_pins(&state.sda_pins) {
+            assert!(
+                !recorded_pins(&state.scl_pins).any(|p| p == pin),
+                "SDA and SCL are both connected to GPIO{}",
+                pin
+            );
+        }
+
+        // Only the static state of the other instances is accessed
+        let others = (0..)
+            .map_while(|index| unsafe { AnyI2c::steal(index) })
+            .map(|i2c| i2c.parts().1)
+            .filter(|other| !core::ptr::eq(*other, state));
+        for other in others {
+            for pin in other.connected_pins() {
+                assert!(
+                    !state.connected_pins().any(|p| p == pin),
+                    "GPIO{} is already connected to another I2C peripheral",
+                    pin
+                );
+            }
+        }
+    }
+
     fn connect_pin(
         pin: impl Peripheral<P = impl PeripheralOutput> + 'd,
         input: InputSignal,
         output: OutputSignal,
+        push_pull: bool,
         guard: &mut PinGuard,
     ) {
         crate::into_mapped_ref!(pin);
         // avoid the pin going low during configuration
         pin.set_output_high(true);
 
-        pin.set_to_open_drain_output();
+        if push_pull {
+            pin.set_to_push_pull_output();
+        } else {
+            pin.set_to_open_drain_output();
+        }
         pin.enable_input(true);
         pin.pull_direction(Pull::Up);
 
@@ -591,6 +2272,33 @@ impl<'d, Dm: DriverMode> I2c<'d, Dm> {
 
         *guard = OutputConnection::connect_with_guard(pin, output);
     }
+
+    /// Connects separate input and output pins, and returns the number of the
+    /// input pin.
+    fn connect_split_pins(
+        input_pin: impl Peripheral<P = impl PeripheralInput> + 'd,
+        output_pin: impl Peripheral<P = impl PeripheralOutput> + 'd,
+        input: InputSignal,
+        output: OutputSignal,
+        guard: &mut PinGuard,
+    ) -> Option<u8> {
+        crate::into_mapped_ref!(input_pin, output_pin);
+        // avoid the pin going low during configuration
+        output_pin.set_output_high(true);
+
+        output_pin.set_to_open_drain_output();
+        // keep the level of the output pin readable, e.g. for `bus_state`
+        output_pin.enable_input(true);
+        output_pin.pull_direction(Pull::Up);
+
+        input_pin.init_input(Pull::Up);
+        let input_pin_number = input_pin.pin_number();
+        input.connect_to(input_pin);
+
+        *guard = OutputConnection::connect_with_guard(output_pin, output);
+
+        input_pin_number
+    }
 }
 
 impl<'d> I2c<'d, Blocking> {
@@ -606,16 +2314,30 @@ impl<'d> I2c<'d, Blocking> {
         let sda_pin = PinGuard::new_unconnected(i2c.info().sda_output);
         let scl_pin = PinGuard::new_unconnected(i2c.info().scl_output);
 
+        let bus_release = BusRelease {
+            info: i2c.info(),
+            multi_master: config.multi_master,
+        };
+
+        let pin_record = PinRecord::new(i2c.state());
+
         let i2c = I2c {
             i2c,
             phantom: PhantomData,
             config,
+            bus_release,
             guard,
             sda_pin,
             scl_pin,
+            pin_record,
+            scl_push_pull: false,
+            wait_for_interrupt: None,
+            user_handler: false,
         };
 
+        i2c.driver().state.scl_pin.store(NO_PIN, Ordering::Relaxed);
         i2c.driver().setup(&i2c.config)?;
+        i2c.apply_line_inversion();
 
         Ok(i2c)
     }
@@ -634,9 +2356,42 @@ impl<'d> I2c<'d, Blocking> {
     ///
     /// You can restore the default/unhandled interrupt handler by using
     /// [crate::DEFAULT_INTERRUPT_HANDLER]
+    ///
+    /// This stops blocking operations from waiting for interrupts (see
+    /// [`Self::set_wait_for_interrupt`]), as they rely on the driver's own
+    /// handler.
     #[instability::unstable]
     pub fn set_interrupt_handler(&mut self, handler: InterruptHandler) {
         self.i2c.info().set_interrupt_handler(handler);
+        self.wait_for_interrupt = None;
+        self.user_handler =
+            handler.handler() as usize != crate::DEFAULT_INTERRUPT_HANDLER.handler() as usize;
+    }
+
+    /// Registers an interrupt handler for the peripheral, like
+    /// [`Self::set_interrupt_handler`], and returns the driver.
+    ///
+    /// This allows registering the handler while building the driver:
+    ///
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// #[handler]
+    /// fn i2c_handler() {
+    ///     // handle the interrupt
+    /// }
+    ///
+    /// let i2c = I2c::new(peripherals.I2C0, Config::default())
+    ///     .unwrap()
+    ///     .with_sda(peripherals.GPIO1)
+    ///     .with_scl(peripherals.GPIO2)
+    ///     .with_interrupt_handler(i2c_handler);
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn with_interrupt_handler(mut self, handler: InterruptHandler) -> Self {
+        self.set_interrupt_handler(handler);
+        self
     }
 
     /// Listen for the given interrupts
@@ -663,21 +2418,101 @@ impl<'d> I2c<'d, Blocking> {
         self.i2c.info().clear_interrupts(interrupts)
     }
 
+    /// Lets blocking operations put the CPU to sleep while waiting for the
+    /// peripheral, instead of busy-polling its status registers.
+    ///
+    /// When enabled, the driver binds its own interrupt handler on the current
+    /// core and waits for the I2C interrupt with `WFI` (RISC-V) or `WAITI`
+    /// (Xtensa). This reduces the power consumption during long transfers.
+    ///
+    /// Returns whether waiting for interrupts is enabled. A handler registered
+    /// with [`Self::set_interrupt_handler`] is never replaced: in that case
+    /// this returns `false` and operations keep polling. Restore
+    /// [crate::DEFAULT_INTERRUPT_HANDLER] first to enable waiting.
+    ///
+    /// The CPU is only put to sleep if interrupts are enabled when the
+    /// operation is started, e.g. not inside a critical section, and if the
+    /// bus timeout isn't [`BusTimeout::Disabled`], so that a stuck bus still
+    /// wakes it up.
+    #[cfg_attr(multi_core, doc = "")]
+    #[cfg_attr(
+        multi_core,
+        doc = "The interrupt is only delivered to the core that called this function. After moving the driver to another core, operations poll again until this function is called on that core."
+    )]
+    #[instability::unstable]
+    pub fn set_wait_for_interrupt(&mut self, enable: bool) -> bool {
+        if !enable {
+            self.wait_for_interrupt = None;
+            return false;
+        }
+
+        if self.user_handler {
+            return false;
+        }
+
+        let info = self.i2c.info();
+        info.set_interrupt_handler(info.async_handler);
+        self.wait_for_interrupt = Some(Cpu::current());
+        true
+    }
+
     /// Configures the I2C peripheral to operate in asynchronous mode.
-    pub fn into_async(mut self) -> I2c<'d, Async> {
-        self.set_interrupt_handler(self.driver().info.async_handler);
+    pub fn into_async(self) -> I2c<'d, Async> {
+        let priority = self.driver().info.async_handler.priority();
+        self.into_async_with_priority(priority)
+    }
+
+    /// Configures the I2C peripheral to operate in asynchronous mode, with
+    /// the interrupt handler running at the given priority.
+    ///
+    /// This is useful for latency-sensitive applications that need the I2C
+    /// interrupt to preempt other interrupt handlers.
+    #[instability::unstable]
+    pub fn into_async_with_priority(mut self, priority: Priority) -> I2c<'d, Async> {
+        let handler = self.driver().info.async_handler.handler();
+        self.set_interrupt_handler(InterruptHandler::new(handler, priority));
+        self.into_async_mode()
+    }
+
+    /// Configures the I2C peripheral to operate in asynchronous mode, or
+    /// returns the blocking driver if the interrupt can't be enabled.
+    ///
+    /// [`Self::into_async`] panics if the interrupt can't be enabled, e.g.
+    /// because no CPU interrupt is available for its priority. This function
+    /// returns the error together with the driver instead, so the application
+    /// can keep using the driver in blocking mode. Note that the driver's
+    /// interrupt handler has been bound even if enabling the interrupt failed,
+    /// replacing a handler registered with [`Self::set_interrupt_handler`].
+    #[instability::unstable]
+    pub fn try_into_async(self) -> Result<I2c<'d, Async>, (Self, crate::interrupt::Error)> {
+        let handler = self.driver().info.async_handler;
+        if let Err(error) = self.driver().info.try_set_interrupt_handler(handler) {
+            return Err((self, error));
+        }
+
+        Ok(self.into_async_mode())
+    }
 
+    fn into_async_mode(self) -> I2c<'d, Async> {
         I2c {
             i2c: self.i2c,
             phantom: PhantomData,
             config: self.config,
+            bus_release: self.bus_release,
             guard: self.guard,
             sda_pin: self.sda_pin,
             scl_pin: self.scl_pin,
+            pin_record: self.pin_record,
+            scl_push_pull: self.scl_push_pull,
+            wait_for_interrupt: None,
+            user_handler: false,
         }
     }
 
     /// Writes bytes to slave with address `address`
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty. Use
+    /// [`Self::probe`] to check whether a device acknowledges its address.
     /// ```rust, no_run
     #[doc = crate::before_snippet!()]
     /// # use esp_hal::i2c::master::{Config, I2c};
@@ -691,12 +2526,17 @@ impl<'d> I2c<'d, Blocking> {
     /// # }
     /// ```
     pub fn write<A: Into<I2cAddress>>(&mut self, address: A, buffer: &[u8]) -> Result<(), Error> {
-        self.driver()
-            .write_blocking(address.into(), buffer, true, true)
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.transaction_impl(address.into(), [Operation::Write(buffer)].into_iter())
             .inspect_err(|_| self.internal_recover())
     }
 
     /// Reads enough bytes from slave with `address` to fill `buffer`
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
     /// ```rust, no_run
     #[doc = crate::before_snippet!()]
     /// # use esp_hal::i2c::master::{Config, I2c};
@@ -715,20 +2555,68 @@ impl<'d> I2c<'d, Blocking> {
         address: A,
         buffer: &mut [u8],
     ) -> Result<(), Error> {
-        self.driver()
-            .read_blocking(address.into(), buffer, true, true, false)
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.transaction_impl(address.into(), [Operation::Read(buffer)].into_iter())
             .inspect_err(|_| self.internal_recover())
     }
 
-    /// Writes bytes to slave with address `address` and then reads enough bytes
-    /// to fill `buffer` *in a single transaction*
+    /// Reads enough bytes from slave with `address` to fill the uninitialized
+    /// `buffer`, returning it as an initialized slice.
+    ///
+    /// This avoids having to initialize the buffer only for [`Self::read`] to
+    /// overwrite it.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    ///
     /// ```rust, no_run
     #[doc = crate::before_snippet!()]
+    /// # use core::mem::MaybeUninit;
     /// # use esp_hal::i2c::master::{Config, I2c};
-    /// # let mut i2c = I2c::new(
-    /// #   peripherals.I2C0,
-    /// #   Config::default(),
-    /// # )
+    /// # let mut i2c = I2c::new(peripherals.I2C0, Config::default()).unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let mut buffer = [MaybeUninit::uninit(); 64];
+    /// let data = i2c.read_uninit(DEVICE_ADDR, &mut buffer).unwrap();
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn read_uninit<'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        buffer: &'b mut [MaybeUninit<u8>],
+    ) -> Result<&'b mut [u8], Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let len = buffer.len();
+        let mut slots = buffer.iter_mut();
+        self.driver()
+            .read_stream_blocking(address.into(), len, &mut |byte| {
+                if let Some(slot) = slots.next() {
+                    slot.write(byte);
+                }
+            })
+            .inspect_err(|_| self.internal_recover())?;
+
+        // SAFETY: the read only succeeds after `len` bytes have been passed to the
+        // closure, so every element of `buffer` has been written.
+        Ok(unsafe { &mut *(buffer as *mut [MaybeUninit<u8>] as *mut [u8]) })
+    }
+
+    /// Writes bytes to slave with address `address` and then reads enough bytes
+    /// to fill `buffer` *in a single transaction*
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if either buffer is empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
     /// # .unwrap();
     /// # const DEVICE_ADDR: u8 = 0x77;
     /// let mut data = [0u8; 22];
@@ -741,17 +2629,324 @@ impl<'d> I2c<'d, Blocking> {
         write_buffer: &[u8],
         read_buffer: &mut [u8],
     ) -> Result<(), Error> {
-        let address = address.into();
+        if write_buffer.is_empty() || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.transaction_impl(
+            address.into(),
+            [Operation::Write(write_buffer), Operation::Read(read_buffer)].into_iter(),
+        )
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes the bytes of `first` followed by the bytes of `second` to slave
+    /// with address `address` *in a single write*
+    ///
+    /// The two buffers are sent back to back without a repeated START between
+    /// them, followed by a STOP condition. This is useful to send a command
+    /// followed by a separately stored payload.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if both buffers are empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let payload = [0xde, 0xad, 0xbe, 0xef];
+    /// i2c.write_write(DEVICE_ADDR, &[0xaa], &payload).ok();
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn write_write<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        first: &[u8],
+        second: &[u8],
+    ) -> Result<(), Error> {
+        if first.is_empty() && second.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.transaction_impl(
+            address.into(),
+            [Operation::Write(first), Operation::Write(second)].into_iter(),
+        )
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes the bytes of all `write_parts` to slave with address `address`
+    /// and then reads enough bytes to fill `read_buffer` *in a single
+    /// transaction*
+    ///
+    /// The parts are sent back to back as one continuous write, followed by a
+    /// repeated START and the read. This avoids joining e.g. a register
+    /// address and a payload into a temporary buffer.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if all parts or `read_buffer` are
+    /// empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let payload = [0x01, 0x02];
+    /// let mut data = [0u8; 4];
+    /// i2c.write_read_parts(DEVICE_ADDR, &[&[0xaa], &payload], &mut data)
+    ///     .ok();
+    /// # }
+    /// ```
+    pub fn write_read_parts<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        write_parts: &[&[u8]],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if write_parts.iter().all(|part| part.is_empty()) || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let operations = write_parts
+            .iter()
+            .map(|part| Operation::Write(part))
+            .chain(core::iter::once(Operation::Read(read_buffer)));
+
+        self.transaction_impl(address.into(), operations)
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes `first` and then `second` to slave with address `address`, with a
+    /// repeated START in between, and then reads enough bytes to fill
+    /// `read_buffer` *in a single transaction*
+    ///
+    /// Unlike [`Self::write_write`], the second write starts with a repeated
+    /// START and the address again. This is needed by devices that expect
+    /// multi-phase commands, e.g. an unlock sequence followed by a register
+    /// read. The transaction ends with a single STOP condition after the read.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if any of the buffers is empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let mut data = [0u8; 2];
+    /// i2c.write_then_write_read(DEVICE_ADDR, &[0x5a], &[0x10], &mut data)
+    ///     .ok();
+    /// # }
+    /// ```
+    pub fn write_then_write_read<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        first: &[u8],
+        second: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if first.is_empty() || second.is_empty() || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
 
         self.driver()
-            .write_blocking(address, write_buffer, true, read_buffer.is_empty())
-            .inspect_err(|_| self.internal_recover())?;
+            .write_then_write_read_blocking(address.into(), first, second, read_buffer)
+            .inspect_err(|_| self.internal_recover())
+    }
 
+    /// Sends a STOP condition to release the bus.
+    ///
+    /// This ends a transaction that was left open, e.g. after a manual bus
+    /// recovery or an operation sequence that ended with a repeated START.
+    /// The STOP condition is only sent if the bus is busy, otherwise this
+    /// does nothing.
+    ///
+    /// If the bus is busy because another master is in the middle of a
+    /// transaction, the STOP condition interferes with that transaction and
+    /// may fail with [`Error::ArbitrationLost`].
+    #[instability::unstable]
+    pub fn send_stop(&mut self) -> Result<(), Error> {
         self.driver()
-            .read_blocking(address, read_buffer, true, true, false)
-            .inspect_err(|_| self.internal_recover())?;
+            .send_stop_blocking()
+            .inspect_err(|_| self.internal_recover())
+    }
 
-        Ok(())
+    /// Writes `cmd` to slave with address `address`, waits until the device is
+    /// ready and then reads enough bytes to fill `out`
+    ///
+    /// After writing the command, the register `status_reg` is read every
+    /// [`ReadyPoll::interval`] until any of the bits in `ready_mask` is set.
+    /// If the device doesn't become ready within [`ReadyPoll::timeout`],
+    /// [`Error::Timeout`] is returned.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `cmd` or `out` is empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c, ReadyPoll};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// use esp_hal::time::Duration;
+    ///
+    /// const STATUS: u8 = 0x27;
+    /// const READY: u8 = 0x08;
+    /// let poll = ReadyPoll {
+    ///     interval: Duration::millis(1),
+    ///     timeout: Duration::millis(100),
+    /// };
+    /// let mut data = [0u8; 6];
+    /// i2c.read_when_ready(DEVICE_ADDR, &[0xf4], STATUS, READY, poll, &mut
+    /// data)     .ok();
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn read_when_ready<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        cmd: &[u8],
+        status_reg: u8,
+        ready_mask: u8,
+        poll: ReadyPoll,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if out.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let address = address.into();
+        self.write(address, cmd)?;
+
+        let deadline = crate::time::now() + poll.timeout;
+        loop {
+            let mut status = [0u8];
+            self.write_read(address, &[status_reg], &mut status)?;
+            if status[0] & ready_mask != 0 {
+                break;
+            }
+
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
+            }
+
+            crate::delay::Delay::new().delay(poll.interval);
+        }
+
+        self.read(address, out)
+    }
+
+    /// Checks whether a device acknowledges the address `address`.
+    ///
+    /// This sends the address followed by a STOP condition, without
+    /// transferring any data. Returns `Ok(true)` if the device acknowledged
+    /// its address and `Ok(false)` if it didn't.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// if i2c.probe(DEVICE_ADDR) == Ok(true) {
+    ///     // the device is present
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn probe<A: Into<I2cAddress>>(&mut self, address: A) -> Result<bool, Error> {
+        let result = self
+            .transaction_impl(address.into(), [Operation::Write(&[])].into_iter())
+            .inspect_err(|_| self.internal_recover());
+
+        probe_result(result)
+    }
+
+    /// Checks whether a device is present at `address`, debouncing the result.
+    ///
+    /// The address is probed with [`Self::probe`] until `samples` consecutive
+    /// probes agree, which filters out the flapping of a device behind a
+    /// connector with intermittent contact. A `samples` value of `0` is
+    /// treated as `1`. A probe that fails with an error counts as the device
+    /// being absent.
+    ///
+    /// Consecutive probes are 5 ms apart, so that they span the contact
+    /// bounce of a connector. At most `2 * samples` probes are made. If they
+    /// never agree often enough in a row, the device is reported as absent.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// if i2c.device_present(DEVICE_ADDR, 3) {
+    ///     // the device has been plugged in
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn device_present<A: Into<I2cAddress>>(&mut self, address: A, samples: u8) -> bool {
+        let address = address.into();
+        let samples = samples.max(1);
+
+        let mut present = false;
+        let mut run = 0;
+        for i in 0..2 * u16::from(samples) {
+            if i > 0 {
+                crate::delay::Delay::new().delay(PRESENCE_SETTLE_TIME);
+            }
+
+            let sample = self.probe(address) == Ok(true);
+            run = if sample == present { run + 1 } else { 1 };
+            present = sample;
+            if run == samples {
+                return present;
+            }
+        }
+
+        false
+    }
+
+    /// Writes the bytes produced by `bytes` to slave with address `address`
+    ///
+    /// The length of the data doesn't need to be known in advance: the bytes
+    /// are collected into small chunks as the transfer goes, and a STOP
+    /// condition is issued once the iterator is exhausted.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// i2c.write_iter(DEVICE_ADDR, (0..=255).map(|i| i as u8)).ok();
+    /// # }
+    /// ```
+    pub fn write_iter<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        bytes: impl IntoIterator<Item = u8>,
+    ) -> Result<(), Error> {
+        self.driver()
+            .write_iter_blocking(address.into(), bytes.into_iter())
+            .inspect_err(|_| self.internal_recover())
     }
 
     /// Execute the provided operations on the I2C bus.
@@ -766,6 +2961,10 @@ impl<'d> I2c<'d, Blocking> {
     /// - After executing the last operation an SP is sent automatically.
     /// - If the last operation is a `Read` the master does not send an
     ///   acknowledge for the last byte.
+    /// - A `Delay` operation is preceded by an SP, and the next operation
+    ///   starts with an ST again.
+    /// - A `Read` with an empty buffer is handled as configured by
+    ///   [`Config::empty_read`].
     ///
     /// - `ST` = start condition
     /// - `SAD+R/W` = slave address followed by bit 1 to indicate reading or 0
@@ -789,60 +2988,541 @@ impl<'d> I2c<'d, Blocking> {
     /// ).ok();
     /// # }
     /// ```
-    pub fn transaction<'a, A: Into<I2cAddress>>(
+    pub fn transaction<'a, 'b, A: Into<I2cAddress>>(
         &mut self,
         address: A,
-        operations: impl IntoIterator<Item = &'a mut Operation<'a>>,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
     ) -> Result<(), Error> {
         self.transaction_impl(address.into(), operations.into_iter().map(Operation::from))
             .inspect_err(|_| self.internal_recover())
     }
-}
-
-impl private::Sealed for I2c<'_, Blocking> {}
 
-impl InterruptConfigurable for I2c<'_, Blocking> {
-    fn set_interrupt_handler(&mut self, handler: InterruptHandler) {
-        self.i2c.info().set_interrupt_handler(handler);
+    /// Executes the provided operations like [`Self::transaction`], and
+    /// reports which operation failed if the transaction fails.
+    ///
+    /// On error, [`TransactionError::operation`] is the index of the failed
+    /// operation in `operations`, and [`TransactionError::transferred`] is
+    /// the number of its bytes that were transferred. Errors that occur before
+    /// any data is transferred, like losing the bus to another master, are
+    /// attributed to the operation that was about to start.
+    ///
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c, Operation};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let mut data = [0u8; 22];
+    /// if let Err(error) = i2c.transaction_detailed(
+    ///     DEVICE_ADDR,
+    ///     &mut [Operation::Write(&[0xaa]), Operation::Read(&mut data)],
+    /// ) {
+    ///     let failed_step = error.operation;
+    ///     let bytes_done = error.transferred;
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn transaction_detailed<'a, 'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
+    ) -> Result<(), TransactionError> {
+        let mut progress = Progress::default();
+        self.transaction_impl_tracked(
+            address.into(),
+            operations.into_iter().map(Operation::from),
+            &mut progress,
+        )
+        .map_err(|error| {
+            self.internal_recover();
+            progress.error(error)
+        })
     }
-}
-
-#[cfg_attr(esp32, allow(dead_code))]
-#[derive(Debug, EnumSetType)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[non_exhaustive]
-#[instability::unstable]
-pub enum Event {
-    /// Triggered when op_code of the master indicates an END command and an END
-    /// condition is detected.
-    EndDetect,
-
-    /// Triggered when the I2C controller detects a STOP bit.
-    TxComplete,
 
-    /// Triggered when the TX FIFO watermark check is enabled and the TX fifo
-    /// falls below the configured watermark.
-    #[cfg(not(any(esp32, esp32s2)))]
-    TxFifoWatermark,
-}
+    /// Executes the provided operations like [`Self::transaction`], but
+    /// leaves the bus held instead of releasing it with a STOP condition.
+    ///
+    /// The last operation ends with the peripheral holding SCL low, so the bus
+    /// stays owned by this master. The next transaction continues with a
+    /// repeated START, which allows deciding on the next operation after this
+    /// call has returned. Use [`Self::send_stop`] to release the bus without
+    /// another transaction. If the last operation is a delay, the bus has
+    /// already been released before it.
+    ///
+    /// **The bus is blocked until it is released.** While it is held, no other
+    /// master can use the bus, and the addressed device keeps waiting for the
+    /// transaction to continue. Make sure every code path eventually releases
+    /// the bus, either with a following transaction or with
+    /// [`Self::send_stop`]. The driver only does so on its own when it is
+    /// dropped outside of [`Config::multi_master`] mode. If an operation
+    /// fails, the peripheral is reset as after any failed transaction.
+    #[instability::unstable]
+    pub fn transaction_no_stop<'a, 'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
+    ) -> Result<(), Error> {
+        self.run_transaction(
+            address.into(),
+            operations.into_iter().map(Operation::from),
+            false,
+            &mut Progress::default(),
+        )
+        .inspect_err(|_| self.internal_recover())
+    }
 
-#[cfg(not(esp32))]
+    /// Issues an SMBus quick command to the device with address `address`.
+    ///
+    /// A quick command consists of a START condition, the address with `value`
+    /// as the R/W bit, and a STOP condition. No data is transferred.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// i2c.smbus_quick(DEVICE_ADDR, true).ok();
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn smbus_quick<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        value: bool,
+    ) -> Result<(), Error> {
+        self.driver()
+            .smbus_quick_blocking(address.into(), value)
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Sends a START condition, `raw_byte` and a STOP condition.
+    ///
+    /// Unlike the other operations, `raw_byte` is sent as-is instead of being
+    /// built from a device address and the R/W bit. This is meant for
+    /// non-standard protocols that use the first byte in unusual ways. The
+    /// byte has to be acknowledged, otherwise
+    /// [`Error::AcknowledgeCheckFailed`] is returned.
+    #[instability::unstable]
+    pub fn send_raw_address(&mut self, raw_byte: u8) -> Result<(), Error> {
+        self.driver()
+            .send_raw_address_blocking(raw_byte)
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Reads the SMBus Alert Response Address to find out which device
+    /// asserted the SMBALERT# line.
+    ///
+    /// Returns the 7-bit address of the responding device, or `None` if no
+    /// device acknowledged the Alert Response Address.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// if let Ok(Some(address)) = i2c.smbus_alert_response() {
+    ///     // handle the alert raised by the device at `address`
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn smbus_alert_response(&mut self) -> Result<Option<u8>, Error> {
+        let mut buffer = [0u8; 1];
+        match self.read(SMBUS_ALERT_RESPONSE_ADDRESS, &mut buffer) {
+            Ok(()) => Ok(Some(buffer[0] >> 1)),
+            Err(Error::AcknowledgeCheckFailed(_)) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Performs an SMBus block read of `command` from the device with address
+    /// `address`.
+    ///
+    /// The device first sends the number of bytes that follow, then the data
+    /// bytes. At most `out.len()` of them are read into `out`, the transfer is
+    /// ended early if the device has more to send. Returns the number of
+    /// bytes read.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `out` is empty.
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x0b;
+    /// let mut name = [0u8; 32];
+    /// if let Ok(len) = i2c.smbus_block_read(DEVICE_ADDR, 0x21, &mut name) {
+    ///     let name = &name[..len];
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn smbus_block_read<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        command: u8,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        if out.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.driver()
+            .smbus_block_read_blocking(address.into(), command, out)
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Reads enough bytes from slave with `address` to fill `buffer`, choosing
+    /// whether the final byte is acknowledged.
+    ///
+    /// [`Self::read`] NACKs the final byte, as required by the I2C
+    /// specification. A few devices instead expect the master to ACK every
+    /// byte, which can be done by setting `ack_last` to `true`. The read is
+    /// terminated by a STOP condition either way.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    #[instability::unstable]
+    pub fn read_with_last_ack<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        buffer: &mut [u8],
+        ack_last: bool,
+    ) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let last_ack = if ack_last { Ack::Ack } else { Ack::Nack };
+        self.driver()
+            .read_with_last_ack_blocking(address.into(), buffer, last_ack)
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes bytes to slave with address `address`, choosing whether the
+    /// data bytes have to be acknowledged.
+    ///
+    /// [`Self::write`] returns [`Error::AcknowledgeCheckFailed`] if the device
+    /// NACKs any of the bytes. Some devices NACK the data bytes as part of
+    /// their protocol, e.g. to signal that they won't accept further bytes.
+    /// Setting `check_data_ack` to `false` ignores the ACK bit of the data
+    /// bytes, so such writes don't fail. The address byte still has to be
+    /// acknowledged, so a missing device is reported as usual.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    #[instability::unstable]
+    pub fn write_with_data_ack_check<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        buffer: &[u8],
+        check_data_ack: bool,
+    ) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = Driver {
+            check_data_ack,
+            ..self.driver()
+        };
+        driver
+            .begin_transaction_blocking()
+            .and_then(|_| driver.write_blocking(address.into(), buffer, true, true))
+            .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Starts writing `bytes` to slave with address `address` without waiting
+    /// for the write to complete.
+    ///
+    /// The write is driven by calling [`PolledWrite::poll`], which refills the
+    /// TX FIFO and returns whether the write has completed without blocking.
+    /// This allows servicing the transfer from a super-loop.
+    ///
+    /// The write has to fit into a single command sequence. Returns
+    /// [`Error::ZeroLengthInvalid`] if `bytes` is empty, and
+    /// [`Error::TransferTooLong`] if it is longer than 254 bytes.
+    #[cfg_attr(
+        any(esp32, esp32s2),
+        doc = "\n\nOn this chip, [`Error::FifoExceeded`] is returned for writes longer than 31 bytes."
+    )]
+    /// ```rust, no_run
+    #[doc = crate::before_snippet!()]
+    /// # use esp_hal::i2c::master::{Config, I2c};
+    /// # let mut i2c = I2c::new(
+    /// #   peripherals.I2C0,
+    /// #   Config::default(),
+    /// # )
+    /// # .unwrap();
+    /// # const DEVICE_ADDR: u8 = 0x77;
+    /// let mut write = i2c.start_write(DEVICE_ADDR, &[0xaa, 0xbb]).unwrap();
+    /// while !write.poll().unwrap() {
+    ///     // Do something else
+    /// }
+    /// # }
+    /// ```
+    #[instability::unstable]
+    pub fn start_write<'a, A: Into<I2cAddress>>(
+        &'a mut self,
+        address: A,
+        bytes: &'a [u8],
+    ) -> Result<PolledWrite<'a, 'd>, Error> {
+        if bytes.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = self.driver();
+        let result = driver.begin_transaction_blocking().and_then(|_| {
+            driver.clear_all_interrupts();
+            driver.start_write_operation(address.into(), bytes, true, true)
+        });
+
+        match result {
+            Ok(index) => Ok(PolledWrite {
+                i2c: self,
+                bytes,
+                index,
+                finished: false,
+            }),
+            Err(error) => {
+                self.internal_recover();
+                Err(error)
+            }
+        }
+    }
+
+    /// Starts reading enough bytes from slave with address `address` to fill
+    /// `buffer` without waiting for the read to complete.
+    ///
+    /// The read is driven by calling [`PolledRead::poll`], which drains the
+    /// RX FIFO and returns whether the read has completed without blocking.
+    /// This allows servicing the transfer from a super-loop.
+    ///
+    /// The read has to fit into a single command sequence. Returns
+    /// [`Error::ZeroLengthInvalid`] if `buffer` is empty, and
+    /// [`Error::TransferTooLong`] if it is longer than 254 bytes.
+    #[cfg_attr(
+        any(esp32, esp32s2),
+        doc = "\n\nOn this chip, [`Error::FifoExceeded`] is returned for reads longer than 32 bytes."
+    )]
+    #[instability::unstable]
+    pub fn start_read<'a, A: Into<I2cAddress>>(
+        &'a mut self,
+        address: A,
+        buffer: &'a mut [u8],
+    ) -> Result<PolledRead<'a, 'd>, Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        // The data can only be read once the operation has completed, so it has
+        // to fit into the FIFO
+        #[cfg(any(esp32, esp32s2))]
+        if buffer.len() > 32 {
+            return Err(Error::FifoExceeded);
+        }
+
+        let driver = self.driver();
+        let result = driver.begin_transaction_blocking().and_then(|_| {
+            driver.clear_all_interrupts();
+            driver.start_read_operation(address.into(), buffer.len(), true, true, false, Ack::Nack)
+        });
+
+        match result {
+            Ok(()) => Ok(PolledRead {
+                i2c: self,
+                buffer,
+                index: 0,
+                finished: false,
+            }),
+            Err(error) => {
+                self.internal_recover();
+                Err(error)
+            }
+        }
+    }
+}
+
+/// A write started by [`I2c::start_write`].
+///
+/// Dropping the write before it has completed resets the peripheral.
+#[instability::unstable]
+pub struct PolledWrite<'a, 'd> {
+    i2c: &'a mut I2c<'d, Blocking>,
+    bytes: &'a [u8],
+    index: usize,
+    finished: bool,
+}
+
+impl PolledWrite<'_, '_> {
+    /// Refills the TX FIFO and returns whether the write has completed.
+    ///
+    /// This doesn't block. Once the write has completed or failed, further
+    /// calls return `Ok(true)`.
+    pub fn poll(&mut self) -> Result<bool, Error> {
+        if self.finished {
+            return Ok(true);
+        }
+
+        let driver = self.i2c.driver();
+        let result = driver.check_errors().and_then(|_| {
+            if self.index < self.bytes.len() {
+                self.index += driver.fill_tx_fifo(&self.bytes[self.index..])?;
+            }
+            driver.poll_completion()
+        });
+
+        if result != Ok(false) {
+            self.finished = true;
+        }
+        if result.is_err() {
+            self.i2c.internal_recover();
+        }
+        result
+    }
+}
+
+impl Drop for PolledWrite<'_, '_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.i2c.internal_recover();
+        }
+    }
+}
+
+/// Releases the bus and resets the peripheral when dropped.
+///
+/// Async operations wrap the driver in this while they run, and forget it when
+/// they complete, so that dropping their future mid-transaction doesn't leave
+/// the bus held.
+struct RecoverOnDrop<'a, 'd>(&'a mut I2c<'d, Async>);
+
+impl Drop for RecoverOnDrop<'_, '_> {
+    fn drop(&mut self) {
+        // With multiple masters, a busy bus may be held by another master
+        if !self.0.config.multi_master {
+            self.0.driver().info.release_bus();
+        }
+        self.0.internal_recover();
+    }
+}
+
+/// A read started by [`I2c::start_read`].
+///
+/// Dropping the read before it has completed resets the peripheral.
+#[instability::unstable]
+pub struct PolledRead<'a, 'd> {
+    i2c: &'a mut I2c<'d, Blocking>,
+    buffer: &'a mut [u8],
+    index: usize,
+    finished: bool,
+}
+
+impl PolledRead<'_, '_> {
+    /// Drains the RX FIFO and returns whether the read has completed.
+    ///
+    /// This doesn't block. Once the read has completed or failed, further
+    /// calls return `Ok(true)`. The buffer only holds all of the data once
+    /// this has returned `Ok(true)`.
+    pub fn poll(&mut self) -> Result<bool, Error> {
+        if self.finished {
+            return Ok(true);
+        }
+
+        let driver = self.i2c.driver();
+        let result = driver.poll_completion().and_then(|complete| {
+            // On the ESP32 and ESP32-S2 the data can only be read once the
+            // operation has completed
+            if complete || cfg!(not(any(esp32, esp32s2))) {
+                self.index += driver.read_available_from_fifo(&mut self.buffer[self.index..]);
+            }
+            if complete && self.index < self.buffer.len() {
+                return Err(Error::ExecutionIncomplete);
+            }
+            Ok(complete)
+        });
+
+        if result != Ok(false) {
+            self.finished = true;
+        }
+        if result.is_err() {
+            self.i2c.internal_recover();
+        }
+        result
+    }
+}
+
+impl Drop for PolledRead<'_, '_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.i2c.internal_recover();
+        }
+    }
+}
+
+impl private::Sealed for I2c<'_, Blocking> {}
+
+impl InterruptConfigurable for I2c<'_, Blocking> {
+    fn set_interrupt_handler(&mut self, handler: InterruptHandler) {
+        I2c::set_interrupt_handler(self, handler);
+    }
+}
+
+#[cfg_attr(esp32, allow(dead_code))]
+#[derive(Debug, EnumSetType)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+#[instability::unstable]
+pub enum Event {
+    /// Triggered when op_code of the master indicates an END command and an END
+    /// condition is detected.
+    EndDetect,
+
+    /// Triggered when the I2C controller detects a STOP bit.
+    TxComplete,
+
+    /// Triggered when the TX FIFO watermark check is enabled and the TX fifo
+    /// falls below the configured watermark.
+    #[cfg(not(any(esp32, esp32s2)))]
+    TxFifoWatermark,
+
+    /// Triggered when the RX FIFO watermark check is enabled and the RX fifo
+    /// exceeds the configured watermark.
+    #[cfg(not(any(esp32, esp32s2)))]
+    RxFifoWatermark,
+}
+
+#[cfg(not(esp32))]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 struct I2cFuture<'a> {
     event: Event,
     info: &'a Info,
     state: &'a State,
+    check_data_ack: bool,
 }
 
 #[cfg(not(esp32))]
 impl<'a> I2cFuture<'a> {
-    pub fn new(event: Event, info: &'a Info, state: &'a State) -> Self {
+    pub fn new(event: Event, driver: &Driver<'a>) -> Self {
+        let info = driver.info;
         info.regs().int_ena().modify(|_, w| {
             let w = match event {
                 Event::EndDetect => w.end_detect().set_bit(),
                 Event::TxComplete => w.trans_complete().set_bit(),
                 #[cfg(not(any(esp32, esp32s2)))]
                 Event::TxFifoWatermark => w.txfifo_wm().set_bit(),
+                #[cfg(not(any(esp32, esp32s2)))]
+                Event::RxFifoWatermark => w.rxfifo_wm().set_bit(),
             };
 
             w.arbitration_lost().set_bit();
@@ -852,7 +3532,12 @@ impl<'a> I2cFuture<'a> {
             w
         });
 
-        Self { event, state, info }
+        Self {
+            event,
+            info,
+            state: driver.state,
+            check_data_ack: driver.check_data_ack,
+        }
     }
 
     fn event_bit_is_clear(&self) -> bool {
@@ -863,18 +3548,31 @@ impl<'a> I2cFuture<'a> {
             Event::TxComplete => r.trans_complete().bit_is_clear(),
             #[cfg(not(any(esp32, esp32s2)))]
             Event::TxFifoWatermark => r.txfifo_wm().bit_is_clear(),
+            #[cfg(not(any(esp32, esp32s2)))]
+            Event::RxFifoWatermark => r.rxfifo_wm().bit_is_clear(),
         }
     }
 
     fn check_error(&self) -> Result<(), Error> {
+        let result = self.detect_error();
+        if result.is_err() {
+            BusErrorFlags::record(self.info.regs(), self.state);
+        }
+        result
+    }
+
+    fn detect_error(&self) -> Result<(), Error> {
         let r = self.info.regs().int_raw().read();
 
         if r.arbitration_lost().bit_is_set() {
+            self.state
+                .arbitration_losses
+                .fetch_add(1, Ordering::Relaxed);
             return Err(Error::ArbitrationLost);
         }
 
         if r.time_out().bit_is_set() {
-            return Err(Error::Timeout);
+            return Err(timeout_error(self.state));
         }
 
         if r.nack().bit_is_set() {
@@ -884,7 +3582,9 @@ impl<'a> I2cFuture<'a> {
         }
 
         #[cfg(not(esp32))]
-        if r.trans_complete().bit_is_set() && self.info.regs().sr().read().resp_rec().bit_is_clear()
+        if self.check_data_ack
+            && r.trans_complete().bit_is_set()
+            && self.info.regs().sr().read().resp_rec().bit_is_clear()
         {
             return Err(Error::AcknowledgeCheckFailed(
                 AcknowledgeCheckFailedReason::Data,
@@ -925,57 +3625,365 @@ impl<'d> I2c<'d, Async> {
             i2c: self.i2c,
             phantom: PhantomData,
             config: self.config,
+            bus_release: self.bus_release,
             guard: self.guard,
             sda_pin: self.sda_pin,
             scl_pin: self.scl_pin,
+            pin_record: self.pin_record,
+            scl_push_pull: self.scl_push_pull,
+            wait_for_interrupt: None,
+            user_handler: false,
         }
     }
 
+    /// Waits until the peripheral has finished all queued commands and the
+    /// bus is released.
+    ///
+    /// Operations complete before their futures resolve, so this only has an
+    /// effect after a future was dropped before completing. A command sequence
+    /// that is still running is allowed to finish, and a STOP condition is
+    /// sent if the sequence left the bus held. This is useful before acting on
+    /// the assumption that the STOP condition is on the wire, e.g. before
+    /// toggling a GPIO that other devices on the bus react to. If nothing is
+    /// in flight, this returns immediately.
+    ///
+    /// With [`Config::multi_master`] set, this waits for the bus to become
+    /// idle instead, as the bus may be held by another master.
+    #[instability::unstable]
+    pub async fn wait_idle(&mut self) -> Result<(), Error> {
+        self.driver()
+            .wait_idle()
+            .await
+            .inspect_err(|_| self.internal_recover())
+    }
+
     /// Writes bytes to slave with address `address`
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty. Use
+    /// [`Self::probe`] to check whether a device acknowledges its address.
     pub async fn write<A: Into<I2cAddress>>(
         &mut self,
         address: A,
         buffer: &[u8],
     ) -> Result<(), Error> {
-        self.driver()
-            .write(address.into(), buffer, true, true)
-            .await
-            .inspect_err(|_| self.internal_recover())
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(address.into(), [Operation::Write(buffer)].into_iter()),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
     }
 
     /// Reads enough bytes from slave with `address` to fill `buffer`
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
     pub async fn read<A: Into<I2cAddress>>(
         &mut self,
         address: A,
         buffer: &mut [u8],
     ) -> Result<(), Error> {
-        self.driver()
-            .read(address.into(), buffer, true, true, false)
-            .await
-            .inspect_err(|_| self.internal_recover())
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(address.into(), [Operation::Read(buffer)].into_iter()),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
     }
 
     /// Writes bytes to slave with address `address` and then reads enough
     /// bytes to fill `buffer` *in a single transaction*
+    ///
+    /// This is cancel-safe: if the future is dropped before it completes, e.g.
+    /// between the write and the read, a STOP condition is sent and the
+    /// peripheral is reset, so the bus isn't left held for the next
+    /// transaction.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if either buffer is empty.
     pub async fn write_read<A: Into<I2cAddress>>(
         &mut self,
         address: A,
         write_buffer: &[u8],
         read_buffer: &mut [u8],
     ) -> Result<(), Error> {
-        let address = address.into();
+        if write_buffer.is_empty() || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
 
-        self.driver()
-            .write(address, write_buffer, true, read_buffer.is_empty())
-            .await
-            .inspect_err(|_| self.internal_recover())?;
+        let timeout = self.config.transaction_timeout;
+        let mut guard = RecoverOnDrop(self);
+        let result = with_timeout(
+            timeout,
+            guard.0.transaction_impl_async(
+                address.into(),
+                [Operation::Write(write_buffer), Operation::Read(read_buffer)].into_iter(),
+            ),
+        )
+        .await
+        .inspect_err(|_| guard.0.internal_recover());
 
-        self.driver()
-            .read(address, read_buffer, true, true, false)
+        core::mem::forget(guard);
+        result
+    }
+
+    /// Writes the bytes of `first` followed by the bytes of `second` to slave
+    /// with address `address` *in a single write*
+    ///
+    /// The two buffers are sent back to back without a repeated START between
+    /// them, followed by a STOP condition.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if both buffers are empty.
+    #[instability::unstable]
+    pub async fn write_write<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        first: &[u8],
+        second: &[u8],
+    ) -> Result<(), Error> {
+        if first.is_empty() && second.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(
+                address.into(),
+                [Operation::Write(first), Operation::Write(second)].into_iter(),
+            ),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes the bytes of all `write_parts` to slave with address `address`
+    /// and then reads enough bytes to fill `read_buffer` *in a single
+    /// transaction*
+    ///
+    /// The parts are sent back to back as one continuous write, followed by a
+    /// repeated START and the read.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if all parts or `read_buffer` are
+    /// empty.
+    pub async fn write_read_parts<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        write_parts: &[&[u8]],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if write_parts.iter().all(|part| part.is_empty()) || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let operations = write_parts
+            .iter()
+            .map(|part| Operation::Write(part))
+            .chain(core::iter::once(Operation::Read(read_buffer)));
+
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(address.into(), operations),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes `first` and then `second` to slave with address `address`, with a
+    /// repeated START in between, and then reads enough bytes to fill
+    /// `read_buffer` *in a single transaction*
+    ///
+    /// Unlike [`Self::write_write`], the second write starts with a repeated
+    /// START and the address again. This is needed by devices that expect
+    /// multi-phase commands, e.g. an unlock sequence followed by a register
+    /// read. The transaction ends with a single STOP condition after the read.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if any of the buffers is empty.
+    pub async fn write_then_write_read<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        first: &[u8],
+        second: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if first.is_empty() || second.is_empty() || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.write_then_write_read(address.into(), first, second, read_buffer),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Sends a STOP condition to release the bus.
+    ///
+    /// This ends a transaction that was left open, e.g. after a manual bus
+    /// recovery or an operation sequence that ended with a repeated START.
+    /// The STOP condition is only sent if the bus is busy, otherwise this
+    /// does nothing.
+    ///
+    /// If the bus is busy because another master is in the middle of a
+    /// transaction, the STOP condition interferes with that transaction and
+    /// may fail with [`Error::ArbitrationLost`].
+    #[instability::unstable]
+    pub async fn send_stop(&mut self) -> Result<(), Error> {
+        let driver = self.driver();
+        with_timeout(self.config.transaction_timeout, driver.send_stop())
             .await
-            .inspect_err(|_| self.internal_recover())?;
+            .inspect_err(|_| self.internal_recover())
+    }
 
-        Ok(())
+    /// Writes `cmd` to slave with address `address`, waits until the device is
+    /// ready and then reads enough bytes to fill `out`
+    ///
+    /// After writing the command, the register `status_reg` is read every
+    /// [`ReadyPoll::interval`] until any of the bits in `ready_mask` is set.
+    /// If the device doesn't become ready within [`ReadyPoll::timeout`],
+    /// [`Error::Timeout`] is returned.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `cmd` or `out` is empty.
+    #[instability::unstable]
+    pub async fn read_when_ready<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        cmd: &[u8],
+        status_reg: u8,
+        ready_mask: u8,
+        poll: ReadyPoll,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if out.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let address = address.into();
+        self.write(address, cmd).await?;
+
+        let deadline = crate::time::now() + poll.timeout;
+        loop {
+            let mut status = [0u8];
+            self.write_read(address, &[status_reg], &mut status).await?;
+            if status[0] & ready_mask != 0 {
+                break;
+            }
+
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
+            }
+
+            delay(poll.interval).await;
+        }
+
+        self.read(address, out).await
+    }
+
+    /// Checks whether a device acknowledges the address `address`.
+    ///
+    /// This sends the address followed by a STOP condition, without
+    /// transferring any data. Returns `Ok(true)` if the device acknowledged
+    /// its address and `Ok(false)` if it didn't.
+    #[instability::unstable]
+    pub async fn probe<A: Into<I2cAddress>>(&mut self, address: A) -> Result<bool, Error> {
+        let timeout = self.config.transaction_timeout;
+        let result = with_timeout(
+            timeout,
+            self.transaction_impl_async(address.into(), [Operation::Write(&[])].into_iter()),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover());
+
+        probe_result(result)
+    }
+
+    /// Checks whether a device is present at `address`, debouncing the result.
+    ///
+    /// The address is probed with [`Self::probe`] until `samples` consecutive
+    /// probes agree, which filters out the flapping of a device behind a
+    /// connector with intermittent contact. A `samples` value of `0` is
+    /// treated as `1`. A probe that fails with an error counts as the device
+    /// being absent.
+    ///
+    /// Consecutive probes are 5 ms apart, so that they span the contact
+    /// bounce of a connector. At most `2 * samples` probes are made. If they
+    /// never agree often enough in a row, the device is reported as absent.
+    #[instability::unstable]
+    pub async fn device_present<A: Into<I2cAddress>>(&mut self, address: A, samples: u8) -> bool {
+        let address = address.into();
+        let samples = samples.max(1);
+
+        let mut present = false;
+        let mut run = 0;
+        for i in 0..2 * u16::from(samples) {
+            if i > 0 {
+                delay(PRESENCE_SETTLE_TIME).await;
+            }
+
+            let sample = self.probe(address).await == Ok(true);
+            run = if sample == present { run + 1 } else { 1 };
+            present = sample;
+            if run == samples {
+                return present;
+            }
+        }
+
+        false
+    }
+
+    /// Probes every non-reserved 7-bit address and returns which of them
+    /// acknowledged.
+    ///
+    /// The result is a bitmap over the 7-bit address space: address `a` is
+    /// present if bit `a % 8` of byte `a / 8` is set. The reserved addresses
+    /// `0x00..=0x07` and `0x78..=0x7F` are not probed and are reported as
+    /// absent.
+    ///
+    /// A probe that fails with an error, e.g. [`Error::Timeout`] or
+    /// [`Error::ArbitrationLost`], counts as the device being absent. The
+    /// driver recovers from the error and the scan continues with the next
+    /// address, so that a single failure doesn't discard the whole census.
+    #[instability::unstable]
+    pub async fn probe_all(&mut self) -> [u8; 16] {
+        let mut present = [0u8; 16];
+
+        for address in 0x08..0x78u8 {
+            if self.probe(address).await == Ok(true) {
+                present[address as usize / 8] |= 1 << (address % 8);
+            }
+        }
+
+        present
+    }
+
+    /// Writes the bytes produced by `bytes` to slave with address `address`
+    ///
+    /// The length of the data doesn't need to be known in advance: the bytes
+    /// are collected into small chunks as the transfer goes, and a STOP
+    /// condition is issued once the iterator is exhausted.
+    pub async fn write_iter<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        bytes: impl IntoIterator<Item = u8>,
+    ) -> Result<(), Error> {
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.write_iter(address.into(), bytes.into_iter()),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
     }
 
     /// Execute the provided operations on the I2C bus as a single
@@ -991,70 +3999,461 @@ impl<'d> I2c<'d, Async> {
     /// - After executing the last operation an SP is sent automatically.
     /// - If the last operation is a `Read` the master does not send an
     ///   acknowledge for the last byte.
+    /// - A `Delay` operation is preceded by an SP, and the next operation
+    ///   starts with an ST again.
+    /// - A `Read` with an empty buffer is handled as configured by
+    ///   [`Config::empty_read`].
     ///
     /// - `ST` = start condition
     /// - `SAD+R/W` = slave address followed by bit 1 to indicate reading or 0
     ///   to indicate writing
     /// - `SR` = repeated start condition
     /// - `SP` = stop condition
-    pub async fn transaction<'a, A: Into<I2cAddress>>(
+    pub async fn transaction<'a, 'b, A: Into<I2cAddress>>(
         &mut self,
         address: A,
-        operations: impl IntoIterator<Item = &'a mut Operation<'a>>,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
     ) -> Result<(), Error> {
-        self.transaction_impl_async(address.into(), operations.into_iter().map(Operation::from))
-            .await
-            .inspect_err(|_| self.internal_recover())
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(
+                address.into(),
+                operations.into_iter().map(Operation::from),
+            ),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Executes the provided operations like [`Self::transaction`], and
+    /// reports which operation failed if the transaction fails.
+    ///
+    /// On error, [`TransactionError::operation`] is the index of the failed
+    /// operation in `operations`, and [`TransactionError::transferred`] is
+    /// the number of its bytes that were transferred. If the transaction
+    /// times out, the error is attributed to the operation that was being
+    /// executed.
+    #[instability::unstable]
+    pub async fn transaction_detailed<'a, 'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
+    ) -> Result<(), TransactionError> {
+        let timeout = self.config.transaction_timeout;
+        let mut progress = Progress::default();
+        let result = with_timeout(
+            timeout,
+            self.transaction_impl_tracked_async(
+                address.into(),
+                operations.into_iter().map(Operation::from),
+                &mut progress,
+            ),
+        )
+        .await;
+
+        result.map_err(|error| {
+            self.internal_recover();
+            progress.error(error)
+        })
+    }
+
+    /// Executes the provided operations like [`Self::transaction`], but
+    /// leaves the bus held instead of releasing it with a STOP condition.
+    ///
+    /// The last operation ends with the peripheral holding SCL low, so the bus
+    /// stays owned by this master. The next transaction continues with a
+    /// repeated START, which allows deciding on the next operation after this
+    /// call has returned. Use [`Self::send_stop`] to release the bus without
+    /// another transaction. If the last operation is a delay, the bus has
+    /// already been released before it.
+    ///
+    /// **The bus is blocked until it is released.** While it is held, no other
+    /// master can use the bus, and the addressed device keeps waiting for the
+    /// transaction to continue. Make sure every code path eventually releases
+    /// the bus, either with a following transaction or with
+    /// [`Self::send_stop`]. The driver only does so on its own when it is
+    /// dropped outside of [`Config::multi_master`] mode. If an operation
+    /// fails, the peripheral is reset as after any failed transaction.
+    #[instability::unstable]
+    pub async fn transaction_no_stop<'a, 'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
+    ) -> Result<(), Error> {
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.run_transaction_async(
+                address.into(),
+                operations.into_iter().map(Operation::from),
+                false,
+                &mut Progress::default(),
+            ),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Execute the provided operations on the I2C bus as a single
+    /// transaction, failing with [`Error::Timeout`] if they don't complete
+    /// within `timeout`.
+    ///
+    /// See [`Self::transaction`] for the transaction contract. `timeout`
+    /// replaces [`Config::transaction_timeout`] for this transaction.
+    ///
+    /// When the timeout expires, the commands in flight are aborted and the
+    /// peripheral is reset, so the driver can be used again right away. The
+    /// device may have received part of the transaction in that case.
+    ///
+    /// The timeout is tracked with an `embassy-time` timer, so this is only
+    /// available with the `embassy-time` feature.
+    #[cfg(feature = "embassy-time")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embassy-time")))]
+    #[instability::unstable]
+    pub async fn transaction_with_timeout<'a, 'b, A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        operations: impl IntoIterator<Item = &'a mut Operation<'b>>,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        with_timeout(
+            Some(timeout),
+            self.transaction_impl_async(
+                address.into(),
+                operations.into_iter().map(Operation::from),
+            ),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Issues an SMBus quick command to the device with address `address`.
+    ///
+    /// A quick command consists of a START condition, the address with `value`
+    /// as the R/W bit, and a STOP condition. No data is transferred.
+    #[instability::unstable]
+    pub async fn smbus_quick<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        value: bool,
+    ) -> Result<(), Error> {
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.smbus_quick(address.into(), value),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Sends a START condition, `raw_byte` and a STOP condition.
+    ///
+    /// Unlike the other operations, `raw_byte` is sent as-is instead of being
+    /// built from a device address and the R/W bit. This is meant for
+    /// non-standard protocols that use the first byte in unusual ways. The
+    /// byte has to be acknowledged, otherwise
+    /// [`Error::AcknowledgeCheckFailed`] is returned.
+    #[instability::unstable]
+    pub async fn send_raw_address(&mut self, raw_byte: u8) -> Result<(), Error> {
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.send_raw_address(raw_byte),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Reads the SMBus Alert Response Address to find out which device
+    /// asserted the SMBALERT# line.
+    ///
+    /// Returns the 7-bit address of the responding device, or `None` if no
+    /// device acknowledged the Alert Response Address.
+    #[instability::unstable]
+    pub async fn smbus_alert_response(&mut self) -> Result<Option<u8>, Error> {
+        let mut buffer = [0u8; 1];
+        match self.read(SMBUS_ALERT_RESPONSE_ADDRESS, &mut buffer).await {
+            Ok(()) => Ok(Some(buffer[0] >> 1)),
+            Err(Error::AcknowledgeCheckFailed(_)) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Performs an SMBus block read of `command` from the device with address
+    /// `address`.
+    ///
+    /// The device first sends the number of bytes that follow, then the data
+    /// bytes. At most `out.len()` of them are read into `out`, the transfer is
+    /// ended early if the device has more to send. Returns the number of
+    /// bytes read.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `out` is empty.
+    #[instability::unstable]
+    pub async fn smbus_block_read<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        command: u8,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        if out.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.smbus_block_read(address.into(), command, out),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Reads enough bytes from slave with `address` to fill `buffer`, choosing
+    /// whether the final byte is acknowledged.
+    ///
+    /// [`Self::read`] NACKs the final byte, as required by the I2C
+    /// specification. A few devices instead expect the master to ACK every
+    /// byte, which can be done by setting `ack_last` to `true`. The read is
+    /// terminated by a STOP condition either way.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    #[instability::unstable]
+    pub async fn read_with_last_ack<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        buffer: &mut [u8],
+        ack_last: bool,
+    ) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let last_ack = if ack_last { Ack::Ack } else { Ack::Nack };
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.read_with_last_ack(address.into(), buffer, last_ack),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Writes bytes to slave with address `address`, choosing whether the
+    /// data bytes have to be acknowledged.
+    ///
+    /// [`Self::write`] returns [`Error::AcknowledgeCheckFailed`] if the device
+    /// NACKs any of the bytes. Some devices NACK the data bytes as part of
+    /// their protocol, e.g. to signal that they won't accept further bytes.
+    /// Setting `check_data_ack` to `false` ignores the ACK bit of the data
+    /// bytes, so such writes don't fail. The address byte still has to be
+    /// acknowledged, so a missing device is reported as usual.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    #[instability::unstable]
+    pub async fn write_with_data_ack_check<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        buffer: &[u8],
+        check_data_ack: bool,
+    ) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = Driver {
+            check_data_ack,
+            ..self.driver()
+        };
+        with_timeout(self.config.transaction_timeout, async {
+            driver.begin_transaction().await?;
+            driver.write(address.into(), buffer, true, true).await
+        })
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+
+    /// Reads `count` bytes from the device with address `address`, passing
+    /// each byte to `sink` as soon as it has been received.
+    ///
+    /// Unlike [`Self::read`], this doesn't need a buffer large enough to hold
+    /// the whole response, so it can be used to process long reads
+    /// incrementally. The final byte is NACKed as usual.
+    ///
+    /// `sink` is called from within the future, so it should return quickly
+    /// to not stall the bus.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `count` is zero.
+    #[instability::unstable]
+    pub async fn read_stream<A: Into<I2cAddress>>(
+        &mut self,
+        address: A,
+        count: usize,
+        mut sink: impl FnMut(u8),
+    ) -> Result<(), Error> {
+        if count == 0 {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        let driver = self.driver();
+        with_timeout(
+            self.config.transaction_timeout,
+            driver.read_stream(address.into(), count, &mut sink),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
     }
 
     async fn transaction_impl_async<'a>(
         &mut self,
         address: I2cAddress,
         operations: impl Iterator<Item = Operation<'a>>,
+    ) -> Result<(), Error> {
+        self.transaction_impl_tracked_async(address, operations, &mut Progress::default())
+            .await
+    }
+
+    /// Executes a transaction, holding the bus at the end if
+    /// [`Config::coalesce_transactions`] is set.
+    async fn transaction_impl_tracked_async<'a>(
+        &mut self,
+        address: I2cAddress,
+        operations: impl Iterator<Item = Operation<'a>>,
+        progress: &mut Progress,
+    ) -> Result<(), Error> {
+        let coalesce = self.config.coalesce_transactions;
+        if coalesce && self.driver().is_held_for_other_device(address) {
+            self.driver().send_stop().await?;
+        }
+        self.run_transaction_async(address, operations, !coalesce, progress)
+            .await
+    }
+
+    /// Executes the operations of a transaction. If `stop` is false, the
+    /// last operation ends with END instead of STOP, and the bus is left held.
+    ///
+    /// `progress` is kept up to date with the operation being executed, so
+    /// that it identifies the failed operation if an error is returned.
+    async fn run_transaction_async<'a>(
+        &mut self,
+        address: I2cAddress,
+        operations: impl Iterator<Item = Operation<'a>>,
+        stop: bool,
+        progress: &mut Progress,
     ) -> Result<(), Error> {
         let mut last_op: Option<OpKind> = None;
-        // filter out 0 length read operations
+        let empty_read = self.config.empty_read;
+        // filter out 0 length read operations, unless configured otherwise
         let mut op_iter = operations
-            .filter(|op| op.is_write() || !op.is_empty())
+            .enumerate()
+            .filter(|(_, op)| op.is_write() || !op.is_empty() || empty_read != EmptyRead::Skip)
             .peekable();
 
-        while let Some(op) = op_iter.next() {
-            let next_op = op_iter.peek().map(|v| v.kind());
+        while let Some((index, mut op)) = op_iter.next() {
+            let next_op = op_iter.peek().map(|(_, v)| v.kind());
             let kind = op.kind();
-            match op {
-                Operation::Write(buffer) => {
-                    // execute a write operation:
-                    // - issue START/RSTART if op is different from previous
-                    // - issue STOP if op is the last one
-                    self.driver()
-                        .write(
-                            address,
-                            buffer,
-                            !matches!(last_op, Some(OpKind::Write)),
-                            next_op.is_none(),
-                        )
-                        .await?;
+            *progress = Progress {
+                operation: index,
+                transferred: 0,
+            };
+
+            if kind == OpKind::Read && op.is_empty() {
+                if empty_read == EmptyRead::Probe && last_op.is_none() && next_op.is_none() {
+                    return self.driver().smbus_quick(address, true).await;
                 }
-                Operation::Read(buffer) => {
-                    // execute a read operation:
-                    // - issue START/RSTART if op is different from previous
-                    // - issue STOP if op is the last one
-                    // - will_continue is true if there is another read operation next
-                    self.driver()
-                        .read(
-                            address,
-                            buffer,
-                            !matches!(last_op, Some(OpKind::Read)),
-                            next_op.is_none(),
-                            matches!(next_op, Some(OpKind::Read)),
-                        )
-                        .await?;
+                return Err(Error::ZeroLengthInvalid);
+            }
+
+            // A new START follows, make sure no other master owns the bus
+            if matches!(last_op, None | Some(OpKind::Delay)) && kind != OpKind::Delay {
+                self.driver().begin_transaction().await?;
+            }
+
+            let mut retries = 0;
+            loop {
+                #[cfg(feature = "trace")]
+                trace!(
+                    "I2C {} to {:?}: {} bytes",
+                    op.direction(),
+                    address,
+                    op.len()
+                );
+
+                let result = match &mut op {
+                    Operation::Write(buffer) => {
+                        // execute a write operation:
+                        // - issue START/RSTART if op is different from previous
+                        // - issue STOP if op is the last one (unless the bus is to be held) or
+                        //   followed by a delay
+                        self.driver()
+                            .write_counted(
+                                address,
+                                buffer,
+                                !matches!(last_op, Some(OpKind::Write)),
+                                ends_with_stop(next_op, stop),
+                                &mut progress.transferred,
+                            )
+                            .await
+                    }
+                    Operation::Read(buffer) => {
+                        // execute a read operation:
+                        // - issue START/RSTART if op is different from previous
+                        // - issue STOP if op is the last one (unless the bus is to be held) or
+                        //   followed by a delay
+                        // - will_continue is true if there is another read operation next
+                        self.driver()
+                            .read_counted(
+                                address,
+                                buffer,
+                                !matches!(last_op, Some(OpKind::Read)),
+                                ends_with_stop(next_op, stop),
+                                matches!(next_op, Some(OpKind::Read)),
+                                &mut progress.transferred,
+                            )
+                            .await
+                    }
+                    Operation::Delay(us) => {
+                        delay(Duration::micros(*us as u64)).await;
+                        Ok(())
+                    }
+                };
+
+                #[cfg(feature = "trace")]
+                match &result {
+                    Ok(()) => trace!("I2C {} to {:?} done", op.direction(), address),
+                    Err(error) => trace!(
+                        "I2C {} to {:?} failed: {:?}",
+                        op.direction(),
+                        address,
+                        error
+                    ),
+                }
+
+                match result {
+                    // In multi-master mode, back off and retry if another master won
+                    // the arbitration before we got to own the bus. The retry starts
+                    // over as a new transaction, discarding what the lost attempt left
+                    // in the FIFOs.
+                    Err(Error::ArbitrationLost)
+                        if self.config.multi_master
+                            && matches!(last_op, None | Some(OpKind::Delay))
+                            && retries < MAX_ARBITRATION_RETRIES =>
+                    {
+                        retries += 1;
+                        progress.transferred = 0;
+                        self.driver().begin_transaction().await?;
+                    }
+                    result => break result?,
                 }
             }
 
             last_op = Some(kind);
         }
 
+        if !stop && matches!(last_op, Some(OpKind::Write | OpKind::Read)) {
+            self.driver().state.bus_held.store(true, Ordering::Relaxed);
+        }
+
         Ok(())
     }
 }
@@ -1065,14 +4464,89 @@ impl embedded_hal_async::i2c::I2c for I2c<'_, Async> {
         address: u8,
         operations: &mut [EhalOperation<'_>],
     ) -> Result<(), Self::Error> {
-        self.transaction_impl_async(address.into(), operations.iter_mut().map(Operation::from))
-            .await
-            .inspect_err(|_| self.internal_recover())
+        let timeout = self.config.transaction_timeout;
+        with_timeout(
+            timeout,
+            self.transaction_impl_async(address.into(), operations.iter_mut().map(Operation::from)),
+        )
+        .await
+        .inspect_err(|_| self.internal_recover())
+    }
+}
+
+/// Maps the result of an address-only transaction to whether the device
+/// acknowledged its address.
+fn probe_result(result: Result<(), Error>) -> Result<bool, Error> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(Error::AcknowledgeCheckFailed(_)) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Runs `future` to completion, failing with [`Error::Timeout`] if it takes
+/// longer than `timeout`.
+///
+/// The future is raced against an `embassy-time` timer, so the executor can
+/// sleep until either of them wakes it up.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    #[cfg(feature = "embassy-time")]
+    if let Some(timeout) = timeout {
+        let timer = embassy_time::Timer::after_micros(timeout.to_micros());
+        return match embassy_futures::select::select(future, timer).await {
+            embassy_futures::select::Either::First(res) => res,
+            embassy_futures::select::Either::Second(()) => Err(Error::Timeout),
+        };
     }
+
+    // `Config::check_transaction_timeout` rejects timeouts without a timer
+    #[cfg(not(feature = "embassy-time"))]
+    debug_assert!(timeout.is_none());
+
+    future.await
+}
+
+/// Waits for `duration` in an async context.
+///
+/// With the `embassy-time` feature this waits on an `embassy-time` timer, so
+/// the executor can sleep or run other tasks in the meantime. Without it there
+/// is no timer to wait on, and this busy-waits, blocking the executor.
+async fn delay(duration: Duration) {
+    #[cfg(feature = "embassy-time")]
+    embassy_time::Timer::after_micros(duration.to_micros()).await;
+
+    #[cfg(not(feature = "embassy-time"))]
+    crate::delay::Delay::new().delay(duration);
+}
+
+/// Handles the I2C interrupt of the instance described by `info` and
+/// `state`.
+///
+/// This does what the interrupt handler installed by
+/// [`I2c::into_async`] does, and can be called from a custom interrupt
+/// handler instead. See [`I2c::interrupt_parts`] for how to obtain the
+/// arguments.
+#[instability::unstable]
+pub fn handle_interrupt(info: &Info, state: &State) {
+    async_handler(info, state);
 }
 
 fn async_handler(info: &Info, state: &State) {
     let regs = info.regs();
+
+    // Take the timestamp first, so it's as close to the completion as possible
+    let raw = regs.int_raw().read();
+    let enabled = regs.int_ena().read();
+    if (raw.trans_complete().bit_is_set() && enabled.trans_complete().bit_is_set())
+        || (raw.end_detect().bit_is_set() && enabled.end_detect().bit_is_set())
+    {
+        let now = crate::time::now();
+        state.last_completion.with(|t| *t = Some(now));
+    }
+
     regs.int_ena().modify(|_, w| {
         w.end_detect().clear_bit();
         w.trans_complete().clear_bit();
@@ -1080,7 +4554,10 @@ fn async_handler(info: &Info, state: &State) {
         w.time_out().clear_bit();
 
         #[cfg(not(any(esp32, esp32s2)))]
-        w.txfifo_wm().clear_bit();
+        {
+            w.txfifo_wm().clear_bit();
+            w.rxfifo_wm().clear_bit();
+        }
 
         w.nack().clear_bit()
     });
@@ -1088,6 +4565,44 @@ fn async_handler(info: &Info, state: &State) {
     state.waker.wake();
 }
 
+/// Puts the CPU to sleep until an interrupt is pending.
+///
+/// Interrupts are masked from before `enable_events` runs until the CPU is
+/// asleep, so that an interrupt raised in between can't be missed. Returns
+/// immediately if interrupts are already masked, as the CPU might never wake
+/// up in that case.
+fn sleep_until_interrupt(enable_events: impl FnOnce()) {
+    cfg_if::cfg_if! {
+        if #[cfg(riscv)] {
+            use esp_riscv_rt::riscv::{asm::wfi, register::mstatus};
+
+            if !mstatus::read().mie() {
+                return;
+            }
+
+            // WFI also wakes up for interrupts that became pending while masked.
+            crate::interrupt::free(|| {
+                enable_events();
+                wfi();
+            });
+        } else if #[cfg(xtensa)] {
+            if xtensa_lx::interrupt::get_level() != 0 {
+                return;
+            }
+
+            // `interrupt::free` masks interrupts in INTENABLE, which WAITI doesn't
+            // lift, so the run level is raised instead. WAITI lowers it to 0 and
+            // sleeps in one step.
+            let level = unsafe { crate::interrupt::change_current_runlevel(Priority::max()) };
+            enable_events();
+            unsafe {
+                core::arch::asm!("waiti 0");
+                crate::interrupt::change_current_runlevel(level);
+            }
+        }
+    }
+}
+
 /// Sets the filter with a supplied threshold in clock cycles for which a
 /// pulse must be present to pass the filter
 fn set_filter(
@@ -1124,13 +4639,14 @@ fn set_filter(
     }
 }
 
-#[allow(clippy::too_many_arguments, unused)]
-/// Configures the clock and timing parameters for the I2C peripheral.
-fn configure_clock(
-    register_block: &RegisterBlock,
+/// Bus timings, in source clock cycles, as written to the timing registers.
+#[derive(Clone, Copy)]
+struct ClockTimings {
+    #[cfg_attr(any(esp32, esp32s2), allow(dead_code))]
     sclk_div: u32,
     scl_low_period: u32,
     scl_high_period: u32,
+    #[cfg_attr(esp32, allow(dead_code))]
     scl_wait_high_period: u32,
     sda_hold_time: u32,
     sda_sample_time: u32,
@@ -1139,13 +4655,32 @@ fn configure_clock(
     scl_start_hold_time: u32,
     scl_stop_hold_time: u32,
     timeout: BusTimeout,
+}
+
+/// Configures the clock and timing parameters for the I2C peripheral.
+fn configure_clock(
+    register_block: &RegisterBlock,
+    timings: &ClockTimings,
 ) -> Result<(), ConfigError> {
+    let ClockTimings {
+        scl_low_period,
+        scl_high_period,
+        sda_hold_time,
+        sda_sample_time,
+        scl_rstart_setup_time,
+        scl_stop_setup_time,
+        scl_start_hold_time,
+        scl_stop_hold_time,
+        timeout,
+        ..
+    } = *timings;
+
     unsafe {
         // divider
         #[cfg(any(esp32c2, esp32c3, esp32c6, esp32h2, esp32s3))]
         register_block.clk_conf().modify(|_, w| {
             w.sclk_sel().clear_bit();
-            w.sclk_div_num().bits((sclk_div - 1) as u8)
+            w.sclk_div_num().bits((timings.sclk_div - 1) as u8)
         });
 
         // scl period
@@ -1154,7 +4689,8 @@ fn configure_clock(
             .write(|w| w.scl_low_period().bits(scl_low_period as u16));
 
         #[cfg(not(esp32))]
-        let scl_wait_high_period = scl_wait_high_period
+        let scl_wait_high_period = timings
+            .scl_wait_high_period
             .try_into()
             .map_err(|_| ConfigError::FrequencyInvalid)?;
 
@@ -1257,6 +4793,8 @@ impl Info {
                     Event::TxComplete => w.trans_complete().bit(enable),
                     #[cfg(not(any(esp32, esp32s2)))]
                     Event::TxFifoWatermark => w.txfifo_wm().bit(enable),
+                    #[cfg(not(any(esp32, esp32s2)))]
+                    Event::RxFifoWatermark => w.rxfifo_wm().bit(enable),
                 };
             }
             w
@@ -1279,6 +4817,10 @@ impl Info {
         if ints.txfifo_wm().bit_is_set() {
             res.insert(Event::TxFifoWatermark);
         }
+        #[cfg(not(any(esp32, esp32s2)))]
+        if ints.rxfifo_wm().bit_is_set() {
+            res.insert(Event::RxFifoWatermark);
+        }
 
         res
     }
@@ -1293,6 +4835,8 @@ impl Info {
                     Event::TxComplete => w.trans_complete().clear_bit_by_one(),
                     #[cfg(not(any(esp32, esp32s2)))]
                     Event::TxFifoWatermark => w.txfifo_wm().clear_bit_by_one(),
+                    #[cfg(not(any(esp32, esp32s2)))]
+                    Event::RxFifoWatermark => w.rxfifo_wm().clear_bit_by_one(),
                 };
             }
             w
@@ -1300,18 +4844,60 @@ impl Info {
     }
 
     fn set_interrupt_handler(&self, handler: InterruptHandler) {
+        unwrap!(self.try_set_interrupt_handler(handler));
+    }
+
+    fn try_set_interrupt_handler(
+        &self,
+        handler: InterruptHandler,
+    ) -> Result<(), crate::interrupt::Error> {
         for core in crate::Cpu::other() {
             crate::interrupt::disable(core, self.interrupt);
         }
         self.enable_listen(EnumSet::all(), false);
         self.clear_interrupts(EnumSet::all());
         unsafe { crate::interrupt::bind_interrupt(self.interrupt, handler.handler()) };
-        unwrap!(crate::interrupt::enable(self.interrupt, handler.priority()));
+        crate::interrupt::enable(self.interrupt, handler.priority())
     }
 
     fn disable_interrupts(&self) {
         crate::interrupt::disable(crate::Cpu::current(), self.interrupt);
     }
+
+    /// Sends a STOP condition if the bus is busy.
+    ///
+    /// A command sequence that may still be running is allowed to finish
+    /// first. If it ended with a STOP condition, nothing else is done.
+    fn release_bus(&self) {
+        let regs = self.regs();
+        let is_bus_busy = || regs.sr().read().bus_busy().bit_is_set();
+
+        let deadline = crate::time::now() + BUS_IDLE_TIMEOUT;
+        while is_bus_busy() && regs.int_raw().read().end_detect().bit_is_clear() {
+            if crate::time::now() > deadline {
+                return;
+            }
+        }
+
+        if !is_bus_busy() {
+            return;
+        }
+
+        for cmd in regs.comd_iter() {
+            cmd.reset();
+        }
+        regs.int_clr()
+            .write(|w| unsafe { w.bits(I2C_LL_INTR_MASK) });
+        if add_cmd(&mut regs.comd_iter(), Command::Stop).is_err() {
+            return;
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32c6, esp32h2, esp32s3))]
+        regs.ctr().modify(|_, w| w.conf_upgate().set_bit());
+        regs.ctr().modify(|_, w| w.trans_start().set_bit());
+
+        while is_bus_busy() && crate::time::now() < deadline {}
+    }
 }
 
 impl PartialEq for Info {
@@ -1326,6 +4912,12 @@ unsafe impl Sync for Info {}
 struct Driver<'a> {
     info: &'a Info,
     state: &'a State,
+    config: &'a Config,
+    scl_push_pull: bool,
+    wait_for_interrupt: bool,
+    // Whether a NACK of a written data byte is an error. The address byte is
+    // always checked.
+    check_data_ack: bool,
 }
 
 impl Driver<'_> {
@@ -1336,15 +4928,18 @@ impl Driver<'_> {
     /// Configures the I2C peripheral with the specified frequency, clocks, and
     /// optional timeout.
     fn setup(&self, config: &Config) -> Result<(), ConfigError> {
+        config.check_transaction_timeout()?;
+
         self.regs().ctr().write(|w| {
             // Set I2C controller to master mode
             w.ms_mode().set_bit();
-            // Use open drain output for SDA and SCL
+            // Use open drain output for SDA and, unless configured otherwise, SCL
             w.sda_force_out().set_bit();
-            w.scl_force_out().set_bit();
-            // Use Most Significant Bit first for sending and receiving data
-            w.tx_lsb_first().clear_bit();
-            w.rx_lsb_first().clear_bit();
+            w.scl_force_out().bit(!self.scl_push_pull);
+            // Set the bit order for sending and receiving data
+            let lsb_first = config.bit_order == BitOrder::LsbFirst;
+            w.tx_lsb_first().bit(lsb_first);
+            w.rx_lsb_first().bit(lsb_first);
             // Ensure that clock is enabled
             w.clk_en().set_bit()
         });
@@ -1367,7 +4962,7 @@ impl Driver<'_> {
                 let clock = clocks.xtal_clock.convert();
             }
         }
-        self.set_frequency(clock, config.frequency, config.timeout)?;
+        self.set_frequency(clock, config)?;
 
         self.update_config();
 
@@ -1397,6 +4992,43 @@ impl Driver<'_> {
         self.reset_command_list();
     }
 
+    /// Rejects the addresses reserved by the I2C specification, unless
+    /// [`Config::allow_reserved_addresses`] is set.
+    fn check_address(&self, address: I2cAddress) -> Result<(), Error> {
+        match address {
+            I2cAddress::SevenBit(0x08..=0x77) => Ok(()),
+            _ if self.config.allow_reserved_addresses => Ok(()),
+            _ => Err(Error::ReservedAddress),
+        }
+    }
+
+    /// Returns whether the bus is held by a transaction to a device other than
+    /// `address`.
+    fn is_held_for_other_device(&self, address: I2cAddress) -> bool {
+        let raw = match address {
+            I2cAddress::SevenBit(addr) => addr,
+        };
+        self.state.bus_held.load(Ordering::Relaxed)
+            && self.state.current_address.load(Ordering::Relaxed) != raw
+    }
+
+    /// Records the address of the device the current operation talks to.
+    fn set_current_address(&self, address: I2cAddress) {
+        let raw = match address {
+            I2cAddress::SevenBit(addr) => addr,
+        };
+        self.state.current_address.store(raw, Ordering::Relaxed);
+    }
+
+    /// Resets the FIFOs and the command list before a transaction, unless
+    /// [`Config::low_latency`] is set.
+    fn prepare_transaction(&self) {
+        if !self.config.low_latency {
+            self.reset_fifo();
+            self.reset_command_list();
+        }
+    }
+
     /// Resets the I2C peripheral's command registers
     fn reset_command_list(&self) {
         // Confirm that all commands that were configured were actually executed
@@ -1405,28 +5037,71 @@ impl Driver<'_> {
         }
     }
 
-    #[cfg(esp32)]
     /// Sets the frequency of the I2C interface by calculating and applying the
     /// associated timings - corresponds to i2c_ll_cal_bus_clk and
     /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(
-        &self,
-        source_clk: HertzU32,
-        bus_freq: HertzU32,
-        timeout: BusTimeout,
-    ) -> Result<(), ConfigError> {
+    fn set_frequency(&self, source_clk: HertzU32, config: &Config) -> Result<(), ConfigError> {
+        configure_clock(self.regs(), &Self::clock_timings(source_clk, config)?)
+    }
+
+    /// Reads back the values written by [`configure_clock`].
+    fn dump_timing(&self) -> TimingRegisters {
+        let regs = self.regs();
+
+        cfg_if::cfg_if! {
+            if #[cfg(any(esp32c2, esp32c3, esp32c6, esp32h2, esp32s3))] {
+                let clock_divider = regs.clk_conf().read().sclk_div_num().bits() as u32 + 1;
+            } else {
+                let clock_divider = 1;
+            }
+        }
+
+        let scl_high = regs.scl_high_period().read();
+        cfg_if::cfg_if! {
+            if #[cfg(esp32)] {
+                let scl_wait_high_period = 0;
+                let timeout = Some(regs.to().read().time_out().bits());
+            } else {
+                let scl_wait_high_period = scl_high.scl_wait_high_period().bits() as u32;
+                let to = regs.to().read();
+                let timeout = to
+                    .time_out_en()
+                    .bit_is_set()
+                    .then(|| to.time_out_value().bits() as u32);
+            }
+        }
+
+        TimingRegisters {
+            clock_divider,
+            scl_low_period: regs.scl_low_period().read().scl_low_period().bits() as u32,
+            scl_high_period: scl_high.scl_high_period().bits() as u32,
+            scl_wait_high_period,
+            sda_hold_time: regs.sda_hold().read().time().bits() as u32,
+            sda_sample_time: regs.sda_sample().read().time().bits() as u32,
+            scl_rstart_setup_time: regs.scl_rstart_setup().read().time().bits() as u32,
+            scl_stop_setup_time: regs.scl_stop_setup().read().time().bits() as u32,
+            scl_start_hold_time: regs.scl_start_hold().read().time().bits() as u32,
+            scl_stop_hold_time: regs.scl_stop_hold().read().time().bits() as u32,
+            timeout,
+        }
+    }
+
+    #[cfg(esp32)]
+    /// Calculates the timings for the configured frequency - corresponds to
+    /// i2c_ll_cal_bus_clk in ESP-IDF
+    fn clock_timings(source_clk: HertzU32, config: &Config) -> Result<ClockTimings, ConfigError> {
         let source_clk = source_clk.raw();
-        let bus_freq = bus_freq.raw();
+        let bus_freq = check_frequency(config.frequency.raw())?;
 
         let half_cycle: u32 = source_clk / bus_freq / 2;
-        let scl_low = half_cycle;
-        let scl_high = half_cycle;
-        let sda_hold = half_cycle / 2;
+        let (scl_low, scl_high) = scl_duty_cycle(config.duty_cycle, bus_freq, half_cycle)?;
+        let sda_hold = scl_low / 2;
         let sda_sample = scl_high / 2;
         let setup = half_cycle;
         let hold = half_cycle;
-        let timeout = BusTimeout::BusCycles(match timeout {
-            BusTimeout::Maximum => 0xF_FFFF,
+        let timeout = BusTimeout::BusCycles(match config.timeout {
+            // The timeout can't be disabled, use the maximum instead
+            BusTimeout::Maximum | BusTimeout::Disabled => 0xF_FFFF,
             BusTimeout::BusCycles(cycles) => check_timeout(cycles * 2 * half_cycle, 0xF_FFFF)?,
         });
 
@@ -1460,21 +5135,20 @@ impl Driver<'_> {
         let scl_high_period = scl_high;
         let scl_low_period = scl_low;
         // sda sample
-        let sda_hold_time = sda_hold;
-        let sda_sample_time = sda_sample;
+        let sda_hold_time = timing_override(config.sda_hold_time, sda_hold)?;
+        let sda_sample_time = timing_override(config.sda_sample_time, sda_sample)?;
         // setup
-        let scl_rstart_setup_time = setup;
-        let scl_stop_setup_time = setup;
+        let scl_rstart_setup_time = timing_override(config.scl_rstart_setup_time, setup)?;
+        let scl_stop_setup_time = timing_override(config.scl_stop_setup_time, setup)?;
         // hold
-        let scl_start_hold_time = hold;
-        let scl_stop_hold_time = hold;
+        let scl_start_hold_time = timing_override(config.scl_start_hold_time, hold)?;
+        let scl_stop_hold_time = timing_override(config.scl_stop_hold_time, hold)?;
 
-        configure_clock(
-            self.regs(),
-            0,
+        Ok(ClockTimings {
+            sclk_div: 0,
             scl_low_period,
             scl_high_period,
-            0,
+            scl_wait_high_period: 0,
             sda_hold_time,
             sda_sample_time,
             scl_rstart_setup_time,
@@ -1482,33 +5156,28 @@ impl Driver<'_> {
             scl_start_hold_time,
             scl_stop_hold_time,
             timeout,
-        )?;
-
-        Ok(())
+        })
     }
 
     #[cfg(esp32s2)]
-    /// Sets the frequency of the I2C interface by calculating and applying the
-    /// associated timings - corresponds to i2c_ll_cal_bus_clk and
-    /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(
-        &self,
-        source_clk: HertzU32,
-        bus_freq: HertzU32,
-        timeout: BusTimeout,
-    ) -> Result<(), ConfigError> {
+    /// Calculates the timings for the configured frequency - corresponds to
+    /// i2c_ll_cal_bus_clk in ESP-IDF
+    fn clock_timings(source_clk: HertzU32, config: &Config) -> Result<ClockTimings, ConfigError> {
         let source_clk = source_clk.raw();
-        let bus_freq = bus_freq.raw();
+        let bus_freq = check_frequency(config.frequency.raw())?;
 
         let half_cycle: u32 = source_clk / bus_freq / 2;
         // SCL
-        let scl_low = half_cycle;
+        let (scl_low, high_cycle) = scl_duty_cycle(config.duty_cycle, bus_freq, half_cycle)?;
         // default, scl_wait_high < scl_high
-        let scl_high = half_cycle / 2 + 2;
-        let scl_wait_high = half_cycle - scl_high;
-        let sda_hold = half_cycle / 2;
+        let scl_wait_high = match config.scl_wait_high {
+            Some(wait_high) => check_scl_wait_high(wait_high, high_cycle)?,
+            None => high_cycle - (high_cycle / 2 + 2),
+        };
+        let scl_high = high_cycle - scl_wait_high;
+        let sda_hold = scl_low / 2;
         // scl_wait_high < sda_sample <= scl_high
-        let sda_sample = half_cycle / 2 - 1;
+        let sda_sample = high_cycle / 2 - 1;
         let setup = half_cycle;
         let hold = half_cycle;
 
@@ -1517,23 +5186,23 @@ impl Driver<'_> {
         let scl_high_period = scl_high;
         let scl_wait_high_period = scl_wait_high;
         // sda sample
-        let sda_hold_time = sda_hold;
-        let sda_sample_time = sda_sample;
+        let sda_hold_time = timing_override(config.sda_hold_time, sda_hold)?;
+        let sda_sample_time = timing_override(config.sda_sample_time, sda_sample)?;
         // setup
-        let scl_rstart_setup_time = setup;
-        let scl_stop_setup_time = setup;
+        let scl_rstart_setup_time = timing_override(config.scl_rstart_setup_time, setup)?;
+        let scl_stop_setup_time = timing_override(config.scl_stop_setup_time, setup)?;
         // hold
-        let scl_start_hold_time = hold - 1;
-        let scl_stop_hold_time = hold;
+        let scl_start_hold_time = timing_override(config.scl_start_hold_time, hold - 1)?;
+        let scl_stop_hold_time = timing_override(config.scl_stop_hold_time, hold)?;
 
-        let timeout = BusTimeout::BusCycles(match timeout {
-            BusTimeout::Maximum => 0xFF_FFFF,
+        let timeout = BusTimeout::BusCycles(match config.timeout {
+            // The timeout can't be disabled, use the maximum instead
+            BusTimeout::Maximum | BusTimeout::Disabled => 0xFF_FFFF,
             BusTimeout::BusCycles(cycles) => check_timeout(cycles * 2 * half_cycle, 0xFF_FFFF)?,
         });
 
-        configure_clock(
-            self.regs(),
-            0,
+        Ok(ClockTimings {
+            sclk_div: 0,
             scl_low_period,
             scl_high_period,
             scl_wait_high_period,
@@ -1544,41 +5213,48 @@ impl Driver<'_> {
             scl_start_hold_time,
             scl_stop_hold_time,
             timeout,
-        )?;
-
-        Ok(())
+        })
     }
 
     #[cfg(any(esp32c2, esp32c3, esp32c6, esp32h2, esp32s3))]
-    /// Sets the frequency of the I2C interface by calculating and applying the
-    /// associated timings - corresponds to i2c_ll_cal_bus_clk and
-    /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(
-        &self,
-        source_clk: HertzU32,
-        bus_freq: HertzU32,
-        timeout: BusTimeout,
-    ) -> Result<(), ConfigError> {
+    /// Calculates the timings for the configured frequency - corresponds to
+    /// i2c_ll_cal_bus_clk in ESP-IDF
+    fn clock_timings(source_clk: HertzU32, config: &Config) -> Result<ClockTimings, ConfigError> {
         let source_clk = source_clk.raw();
-        let bus_freq = bus_freq.raw();
+        let bus_freq = check_frequency(config.frequency.raw())?;
+
+        // The largest divider the 8 bit `sclk_div_num` field can hold
+        const MAX_SCLK_DIV: u32 = 256;
 
-        let clkm_div: u32 = source_clk / (bus_freq * 1024) + 1;
+        // All arithmetic below is checked, so that frequencies the dividers can't
+        // produce are rejected instead of wrapping into a nonsense configuration.
+        let invalid = || ConfigError::FrequencyInvalid;
+        let minus_one = |value: u32| value.checked_sub(1).ok_or_else(invalid);
+
+        let clkm_div: u32 = source_clk / bus_freq.checked_mul(1024).ok_or_else(invalid)? + 1;
+        if clkm_div > MAX_SCLK_DIV {
+            return Err(ConfigError::FrequencyInvalid);
+        }
         let sclk_freq: u32 = source_clk / clkm_div;
         let half_cycle: u32 = sclk_freq / bus_freq / 2;
         // SCL
-        let scl_low = half_cycle;
+        let (scl_low, high_cycle) = scl_duty_cycle(config.duty_cycle, bus_freq, half_cycle)?;
         // default, scl_wait_high < scl_high
         // Make 80KHz as a boundary here, because when working at lower frequency, too
         // much scl_wait_high will faster the frequency according to some
         // hardware behaviors.
-        let scl_wait_high = if bus_freq >= 80 * 1000 {
-            half_cycle / 2 - 2
-        } else {
-            half_cycle / 4
+        // At the upper end (Fast Mode Plus) `half_cycle` is still at least 13
+        // source clock cycles for every supported XTAL frequency, so
+        // `scl_wait_high` stays below `scl_high` and `sda_sample` lands
+        // inside the SCL high period.
+        let scl_wait_high = match config.scl_wait_high {
+            Some(wait_high) => check_scl_wait_high(wait_high, high_cycle)?,
+            None if bus_freq >= 80 * 1000 => (high_cycle / 2).checked_sub(2).ok_or_else(invalid)?,
+            None => high_cycle / 4,
         };
-        let scl_high = half_cycle - scl_wait_high;
-        let sda_hold = half_cycle / 4;
-        let sda_sample = half_cycle / 2 + scl_wait_high;
+        let scl_high = high_cycle - scl_wait_high;
+        let sda_hold = scl_low / 4;
+        let sda_sample = high_cycle / 2 + scl_wait_high;
         let setup = half_cycle;
         let hold = half_cycle;
 
@@ -1589,24 +5265,31 @@ impl Driver<'_> {
         // solution here is not to minus scl_high as well as scl_wait high, and
         // the frequency will be absolutely accurate to all frequency
         // to some extent.
-        let scl_low_period = scl_low - 1;
+        let scl_low_period = minus_one(scl_low)?;
         let scl_high_period = scl_high;
         let scl_wait_high_period = scl_wait_high;
+        if scl_low_period > MAX_TIMING_CYCLES || scl_high_period > MAX_TIMING_CYCLES {
+            return Err(ConfigError::FrequencyInvalid);
+        }
         // sda sample
-        let sda_hold_time = sda_hold - 1;
-        let sda_sample_time = sda_sample - 1;
+        let sda_hold_time = timing_override(config.sda_hold_time, minus_one(sda_hold)?)?;
+        let sda_sample_time = timing_override(config.sda_sample_time, minus_one(sda_sample)?)?;
         // setup
-        let scl_rstart_setup_time = setup - 1;
-        let scl_stop_setup_time = setup - 1;
+        let scl_rstart_setup_time =
+            timing_override(config.scl_rstart_setup_time, minus_one(setup)?)?;
+        let scl_stop_setup_time = timing_override(config.scl_stop_setup_time, minus_one(setup)?)?;
         // hold
-        let scl_start_hold_time = hold - 1;
-        let scl_stop_hold_time = hold - 1;
+        let scl_start_hold_time = timing_override(config.scl_start_hold_time, minus_one(hold)?)?;
+        let scl_stop_hold_time = timing_override(config.scl_stop_hold_time, minus_one(hold)?)?;
 
-        let timeout = match timeout {
+        let timeout = match config.timeout {
             BusTimeout::Maximum => BusTimeout::BusCycles(0x1F),
             BusTimeout::Disabled => BusTimeout::Disabled,
             BusTimeout::BusCycles(cycles) => {
-                let to_peri = (cycles * 2 * half_cycle).max(1);
+                let to_peri = cycles
+                    .checked_mul(2 * half_cycle)
+                    .ok_or(ConfigError::TimeoutInvalid)?
+                    .max(1);
                 let log2 = to_peri.ilog2();
                 // Round up so that we don't shorten timeouts.
                 let raw = if to_peri != 1 << log2 { log2 + 1 } else { log2 };
@@ -1614,9 +5297,8 @@ impl Driver<'_> {
             }
         };
 
-        configure_clock(
-            self.regs(),
-            clkm_div,
+        Ok(ClockTimings {
+            sclk_div: clkm_div,
             scl_low_period,
             scl_high_period,
             scl_wait_high_period,
@@ -1627,9 +5309,7 @@ impl Driver<'_> {
             scl_start_hold_time,
             scl_stop_hold_time,
             timeout,
-        )?;
-
-        Ok(())
+        })
     }
 
     #[cfg(any(esp32, esp32s2))]
@@ -1638,7 +5318,7 @@ impl Driver<'_> {
             return Err(Error::FifoExceeded);
         }
 
-        self.wait_for_completion(false).await?;
+        self.wait_for_completion().await?;
 
         for byte in buffer.iter_mut() {
             *byte = read_fifo(self.regs());
@@ -1673,21 +5353,42 @@ impl Driver<'_> {
         let max_len = if start { 254usize } else { 255usize };
         if bytes.len() > max_len {
             // we could support more by adding multiple write operations
-            return Err(Error::FifoExceeded);
+            return Err(Error::TransferTooLong { max: max_len });
         }
 
-        let write_len = if start { bytes.len() + 1 } else { bytes.len() };
-        // don't issue write if there is no data to write
-        if write_len > 0 {
-            // WRITE command
+        if start && !self.check_data_ack && !bytes.is_empty() {
+            // The address and the data need separate WRITE commands, so that
+            // only the ACK of the address is checked
             add_cmd(
                 cmd_iterator,
                 Command::Write {
                     ack_exp: Ack::Ack,
                     ack_check_en: true,
-                    length: write_len as u8,
+                    length: 1,
+                },
+            )?;
+            add_cmd(
+                cmd_iterator,
+                Command::Write {
+                    ack_exp: Ack::Ack,
+                    ack_check_en: false,
+                    length: bytes.len() as u8,
                 },
             )?;
+        } else {
+            let write_len = if start { bytes.len() + 1 } else { bytes.len() };
+            // don't issue write if there is no data to write
+            if write_len > 0 {
+                // WRITE command
+                add_cmd(
+                    cmd_iterator,
+                    Command::Write {
+                        ack_exp: Ack::Ack,
+                        ack_check_en: start || self.check_data_ack,
+                        length: write_len as u8,
+                    },
+                )?;
+            }
         }
 
         self.update_config();
@@ -1705,34 +5406,37 @@ impl Driver<'_> {
 
     /// Configures the I2C peripheral for a read operation.
     /// - `addr` is the address of the slave device.
-    /// - `buffer` is the buffer to store the read data.
+    /// - `len` is the number of bytes to read.
     /// - `start` indicates whether the operation should start by a START
     ///   condition and sending the address.
     /// - `will_continue` indicates whether there is another read operation
     ///   following this one and we should not nack the last byte.
+    /// - `last_ack` is the ACK value of the last byte, if the read doesn't
+    ///   continue.
     /// - `cmd_iterator` is an iterator over the command registers.
     fn setup_read<'a, I>(
         &self,
         addr: I2cAddress,
-        buffer: &mut [u8],
+        len: usize,
         start: bool,
         will_continue: bool,
+        last_ack: Ack,
         cmd_iterator: &mut I,
     ) -> Result<(), Error>
     where
         I: Iterator<Item = &'a COMD>,
     {
-        if buffer.is_empty() {
+        if len == 0 {
             return Err(Error::ZeroLengthInvalid);
         }
         let (max_len, initial_len) = if will_continue {
-            (255usize, buffer.len())
+            (255usize, len)
         } else {
-            (254usize, buffer.len() - 1)
+            (254usize, len - 1)
         };
-        if buffer.len() > max_len {
+        if len > max_len {
             // we could support more by adding multiple read operations
-            return Err(Error::FifoExceeded);
+            return Err(Error::TransferTooLong { max: max_len });
         }
 
         if start {
@@ -1759,12 +5463,12 @@ impl Driver<'_> {
         }
 
         if !will_continue {
-            // this is the last read so we need to nack the last byte
-            // READ w/o ACK
+            // this is the last read so the last byte gets `last_ack`, which is a
+            // NACK unless the caller asked otherwise
             add_cmd(
                 cmd_iterator,
                 Command::Read {
-                    ack_value: Ack::Nack,
+                    ack_value: last_ack,
                     length: 1,
                 },
             )?;
@@ -1783,13 +5487,28 @@ impl Driver<'_> {
         Ok(())
     }
 
-    #[cfg(not(any(esp32, esp32s2)))]
     /// Reads all bytes from the RX FIFO.
     fn read_all_from_fifo_blocking(&self, buffer: &mut [u8]) -> Result<(), Error> {
+        let len = buffer.len();
+        let mut slots = buffer.iter_mut();
+        self.stream_from_fifo_blocking(len, &mut |byte| {
+            if let Some(slot) = slots.next() {
+                *slot = byte;
+            }
+        })
+    }
+
+    #[cfg(not(any(esp32, esp32s2)))]
+    /// Passes `len` bytes from the RX FIFO to `sink`.
+    fn stream_from_fifo_blocking(
+        &self,
+        len: usize,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
         // Read bytes from FIFO
         // FIXME: Handle case where less data has been provided by the slave than
         // requested? Or is this prevented from a protocol perspective?
-        for byte in buffer.iter_mut() {
+        for _ in 0..len {
             loop {
                 self.check_errors()?;
 
@@ -1799,33 +5518,37 @@ impl Driver<'_> {
                 }
             }
 
-            *byte = read_fifo(self.regs());
+            sink(read_fifo(self.regs()));
         }
 
         Ok(())
     }
 
     #[cfg(any(esp32, esp32s2))]
-    /// Reads all bytes from the RX FIFO.
-    fn read_all_from_fifo_blocking(&self, buffer: &mut [u8]) -> Result<(), Error> {
+    /// Passes `len` bytes from the RX FIFO to `sink`.
+    fn stream_from_fifo_blocking(
+        &self,
+        len: usize,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
         // on ESP32/ESP32-S2 we currently don't support I2C transactions larger than the
         // FIFO apparently it would be possible by using non-fifo mode
         // see https://github.com/espressif/arduino-esp32/blob/7e9afe8c5ed7b5bf29624a5cd6e07d431c027b97/cores/esp32/esp32-hal-i2c.c#L615
 
-        if buffer.len() > 32 {
+        if len > 32 {
             return Err(Error::FifoExceeded);
         }
 
         // wait for completion - then we can just read the data from FIFO
         // once we change to non-fifo mode to support larger transfers that
         // won't work anymore
-        self.wait_for_completion_blocking(false)?;
+        self.wait_for_completion_blocking()?;
 
         // Read bytes from FIFO
         // FIXME: Handle case where less data has been provided by the slave than
         // requested? Or is this prevented from a protocol perspective?
-        for byte in buffer.iter_mut() {
-            *byte = read_fifo(self.regs());
+        for _ in 0..len {
+            sink(read_fifo(self.regs()));
         }
 
         Ok(())
@@ -1858,112 +5581,297 @@ impl Driver<'_> {
         loop {
             self.check_errors()?;
 
-            I2cFuture::new(Event::TxFifoWatermark, self.info, self.state).await?;
+            I2cFuture::new(Event::TxFifoWatermark, self).await?;
+
+            self.regs()
+                .int_clr()
+                .write(|w| w.txfifo_wm().clear_bit_by_one());
+
+            I2cFuture::new(Event::TxFifoWatermark, self).await?;
+
+            if index >= bytes.len() {
+                break Ok(());
+            }
+
+            write_fifo(self.regs(), bytes[index]);
+            index += 1;
+        }
+    }
+
+    #[cfg(not(esp32))]
+    async fn wait_for_completion(&self) -> Result<(), Error> {
+        let end = self.ends_with_end_command();
+        let event = if end {
+            Event::EndDetect
+        } else {
+            Event::TxComplete
+        };
+
+        // The interrupt handler disables all events when any of them fires, so
+        // the future may also resolve for an unrelated event. Only the raw
+        // status of the terminal command's event says that we are done.
+        loop {
+            self.check_errors()?;
+
+            if self.commands_completed(end) {
+                break;
+            }
+
+            I2cFuture::new(event, self).await?;
+        }
+        self.check_all_commands_done()?;
+
+        Ok(())
+    }
+
+    #[cfg(esp32)]
+    async fn wait_for_completion(&self) -> Result<(), Error> {
+        // The ESP32 doesn't wait for the completion interrupts, so we poll the
+        // status instead. The deadline is taken from the system timer rather than
+        // counting polls, so the timeout holds even if a busy executor polls us
+        // only rarely.
+        //
+        // By default the task yields between polls. With the `embassy-time`
+        // feature it sleeps on a timer for about the duration of a byte instead,
+        // so that waiting doesn't keep the executor busy.
+
+        #[cfg(feature = "embassy-time")]
+        let poll_interval = 9 * 1_000_000 / self.config.frequency.raw() as u64;
+
+        let end = self.ends_with_end_command();
+        let deadline = crate::time::now() + self.completion_timeout();
+        loop {
+            self.check_errors()?;
+
+            if self.commands_completed(end) {
+                break;
+            }
+
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
+            }
+
+            #[cfg(feature = "embassy-time")]
+            embassy_time::Timer::after_micros(poll_interval.max(1)).await;
+
+            #[cfg(not(feature = "embassy-time"))]
+            embassy_futures::yield_now().await;
+        }
+        self.check_all_commands_done()?;
+        Ok(())
+    }
+
+    /// Waits for the completion of an I2C transaction.
+    ///
+    /// With [`I2c::set_wait_for_interrupt`] the CPU sleeps until the
+    /// peripheral raises an interrupt, otherwise the status is polled.
+    fn wait_for_completion_blocking(&self) -> Result<(), Error> {
+        let end = self.ends_with_end_command();
+        let deadline = crate::time::now() + self.completion_timeout();
+        loop {
+            self.check_errors()?;
+
+            if self.commands_completed(end) {
+                break;
+            }
+
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
+            }
+
+            self.wait_for_event(false);
+        }
+        self.check_all_commands_done()?;
+        Ok(())
+    }
 
-            self.regs()
-                .int_clr()
-                .write(|w| w.txfifo_wm().clear_bit_by_one());
+    /// Puts the CPU to sleep until the peripheral raises an interrupt, if
+    /// enabled with [`I2c::set_wait_for_interrupt`].
+    ///
+    /// The interrupt handler disables the events again, so they are enabled
+    /// before every wait. If `txfifo_wm` is set, the CPU is also woken up when
+    /// the TX FIFO runs below its watermark.
+    #[cfg_attr(any(esp32, esp32s2), allow(unused_variables))]
+    fn wait_for_event(&self, txfifo_wm: bool) {
+        if !self.wait_for_interrupt || matches!(self.config.timeout, BusTimeout::Disabled) {
+            return;
+        }
 
-            I2cFuture::new(Event::TxFifoWatermark, self.info, self.state).await?;
+        sleep_until_interrupt(|| {
+            self.regs().int_ena().modify(|_, w| {
+                w.end_detect().set_bit();
+                w.trans_complete().set_bit();
+                w.arbitration_lost().set_bit();
+                w.time_out().set_bit();
 
-            if index >= bytes.len() {
-                break Ok(());
-            }
+                #[cfg(not(any(esp32, esp32s2)))]
+                w.txfifo_wm().bit(txfifo_wm);
 
-            write_fifo(self.regs(), bytes[index]);
-            index += 1;
-        }
+                w.nack().set_bit()
+            });
+        });
     }
 
-    #[cfg(not(esp32))]
-    async fn wait_for_completion(&self, end_only: bool) -> Result<(), Error> {
-        self.check_errors()?;
+    /// Returns how long `wait_for_completion` may wait for the hardware before
+    /// reporting a timeout.
+    ///
+    /// This is a few times the duration of transferring a full FIFO, with
+    /// every transfer allowed to be stalled for the configured bus timeout.
+    fn completion_timeout(&self) -> Duration {
+        let bus_freq = self.config.frequency.raw() as u64;
+
+        let timeout_cycles = match self.config.timeout {
+            BusTimeout::BusCycles(cycles) => cycles as u64,
+            // The hardware limit is in the order of seconds or the timeout is
+            // disabled, in both cases give up after about a second.
+            _ => bus_freq,
+        };
 
-        if end_only {
-            I2cFuture::new(Event::EndDetect, self.info, self.state).await?;
-        } else {
-            let res = embassy_futures::select::select(
-                I2cFuture::new(Event::TxComplete, self.info, self.state),
-                I2cFuture::new(Event::EndDetect, self.info, self.state),
-            )
-            .await;
+        // Address byte, data and an extra byte of slack, 9 clock cycles each
+        let chunk_size = I2C_WRITE_CHUNK_SIZE.max(I2C_READ_CHUNK_SIZE);
+        let transfer_cycles = (chunk_size as u64 + 2) * 9;
+
+        let cycles = (transfer_cycles + timeout_cycles) * COMPLETION_TIMEOUT_FACTOR;
+        Duration::micros(cycles * 1_000_000 / bus_freq)
+    }
+
+    /// Returns whether a transaction is in progress on the bus.
+    fn is_bus_busy(&self) -> bool {
+        self.regs().sr().read().bus_busy().bit_is_set()
+    }
 
-            match res {
-                embassy_futures::select::Either::First(res) => res?,
-                embassy_futures::select::Either::Second(res) => res?,
+    /// Waits until no transaction is in progress on the bus.
+    fn wait_for_bus_idle_blocking(&self) -> Result<(), Error> {
+        let deadline = crate::time::now() + BUS_IDLE_TIMEOUT;
+        while self.is_bus_busy() {
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
             }
         }
-        self.check_all_commands_done()?;
 
         Ok(())
     }
 
-    #[cfg(esp32)]
-    async fn wait_for_completion(&self, end_only: bool) -> Result<(), Error> {
-        // for ESP32 we need a timeout here but wasting a timer seems unnecessary
-        // given the short time we spend here
+    /// Waits until no transaction is in progress on the bus.
+    async fn wait_for_bus_idle(&self) -> Result<(), Error> {
+        let deadline = crate::time::now() + BUS_IDLE_TIMEOUT;
+        while self.is_bus_busy() {
+            if crate::time::now() > deadline {
+                return Err(Error::Timeout);
+            }
 
-        let mut tout = MAX_ITERATIONS / 10; // adjust the timeout because we are yielding in the loop
-        loop {
-            let interrupts = self.regs().int_raw().read();
+            embassy_futures::yield_now().await;
+        }
 
-            self.check_errors()?;
+        Ok(())
+    }
 
-            // Handle completion cases
-            // A full transmission was completed (either a STOP condition or END was
-            // processed)
-            if (!end_only && interrupts.trans_complete().bit_is_set())
-                || interrupts.end_detect().bit_is_set()
-            {
-                break;
-            }
+    /// Waits for a command sequence that may still be running to finish, and
+    /// releases the bus if the sequence left it held.
+    async fn wait_idle(&self) -> Result<(), Error> {
+        if self.config.multi_master {
+            return self.wait_for_bus_idle().await;
+        }
 
-            tout -= 1;
-            if tout == 0 {
+        let deadline = crate::time::now() + self.completion_timeout();
+        while self.is_bus_busy() && self.regs().int_raw().read().end_detect().bit_is_clear() {
+            if crate::time::now() > deadline {
                 return Err(Error::Timeout);
             }
 
             embassy_futures::yield_now().await;
         }
-        self.check_all_commands_done()?;
+
+        // If the sequence ended with an END command, send the STOP condition.
+        // This only takes a few bus clock cycles, so it's done blocking.
+        self.info.release_bus();
+
         Ok(())
     }
 
-    /// Waits for the completion of an I2C transaction.
-    fn wait_for_completion_blocking(&self, end_only: bool) -> Result<(), Error> {
-        let mut tout = MAX_ITERATIONS;
-        loop {
-            let interrupts = self.regs().int_raw().read();
-
-            self.check_errors()?;
+    /// Prepares the peripheral for a new transaction.
+    ///
+    /// Waits for the bus to become idle if other masters may be using the bus
+    /// and the previous transaction didn't leave it held, then resets the FIFOs
+    /// and the command list. The operations of the transaction don't reset
+    /// them again: after each successful operation the FIFOs are empty and
+    /// the next operation overwrites the command list from the start, and
+    /// the peripheral is fully reset after a failed one.
+    fn begin_transaction_blocking(&self) -> Result<(), Error> {
+        // A bus held by the previous transaction is busy because of us
+        self.state.last_error_flags.store(0, Ordering::Relaxed);
+        let bus_held = self.state.bus_held.swap(false, Ordering::Relaxed);
+        if self.config.multi_master && !bus_held {
+            self.wait_for_bus_idle_blocking()?;
+        }
+        self.prepare_transaction();
 
-            // Handle completion cases
-            // A full transmission was completed (either a STOP condition or END was
-            // processed)
-            if (!end_only && interrupts.trans_complete().bit_is_set())
-                || interrupts.end_detect().bit_is_set()
-            {
-                break;
-            }
+        Ok(())
+    }
 
-            tout -= 1;
-            if tout == 0 {
-                return Err(Error::Timeout);
-            }
+    /// Prepares the peripheral for a new transaction.
+    ///
+    /// See [`Self::begin_transaction_blocking`].
+    async fn begin_transaction(&self) -> Result<(), Error> {
+        // A bus held by the previous transaction is busy because of us
+        self.state.last_error_flags.store(0, Ordering::Relaxed);
+        let bus_held = self.state.bus_held.swap(false, Ordering::Relaxed);
+        if self.config.multi_master && !bus_held {
+            self.wait_for_bus_idle().await?;
         }
-        self.check_all_commands_done()?;
+        self.prepare_transaction();
+
         Ok(())
     }
 
+    /// Returns whether the configured command list ends with an END command,
+    /// i.e. the operation leaves the bus to be continued by the next one,
+    /// rather than with a STOP condition.
+    fn ends_with_end_command(&self) -> bool {
+        self.regs()
+            .comd_iter()
+            .map(|cmd| cmd.read())
+            .filter(|cmd| cmd.bits() != 0x0)
+            .last()
+            .is_some_and(|cmd| cmd.opcode().is_end())
+    }
+
+    /// Returns whether the command list has been executed up to its terminal
+    /// command.
+    ///
+    /// A STOP command raises `trans_complete` and an END command raises
+    /// `end_detect`, so only the event of the terminal command is checked.
+    /// Accepting either of them would let a stale or early event end the wait
+    /// before the last command has run.
+    fn commands_completed(&self, end: bool) -> bool {
+        let interrupts = self.regs().int_raw().read();
+        if end {
+            interrupts.end_detect().bit_is_set()
+        } else {
+            interrupts.trans_complete().bit_is_set()
+        }
+    }
+
     /// Checks whether all I2C commands have completed execution.
     fn check_all_commands_done(&self) -> Result<(), Error> {
-        // NOTE: on esp32 executing the end command generates the end_detect interrupt
-        //       but does not seem to clear the done bit! So we don't check the done
-        //       status of an end command
+        // The ESP32 generates the end_detect interrupt when executing an END
+        // command, but doesn't set its done bit. The TRMs of the other chips
+        // don't say whether they do, so END commands are checked against the
+        // interrupt, which all of them raise, instead.
+        let end_detected = self.regs().int_raw().read().end_detect().bit_is_set();
         for cmd_reg in self.regs().comd_iter() {
             let cmd = cmd_reg.read();
+            if cmd.bits() == 0x0 {
+                continue;
+            }
+
+            let done = if cmd.opcode().is_end() {
+                end_detected
+            } else {
+                cmd.command_done().bit_is_set()
+            };
 
-            if cmd.bits() != 0x0 && !cmd.opcode().is_end() && !cmd.command_done().bit_is_set() {
+            if !done {
                 return Err(Error::ExecutionIncomplete);
             }
         }
@@ -1971,6 +5879,48 @@ impl Driver<'_> {
         Ok(())
     }
 
+    /// Returns whether the current operation has completed, without waiting.
+    fn poll_completion(&self) -> Result<bool, Error> {
+        self.check_errors()?;
+
+        if !self.commands_completed(self.ends_with_end_command()) {
+            return Ok(false);
+        }
+
+        self.check_all_commands_done()?;
+        Ok(true)
+    }
+
+    #[cfg(not(any(esp32, esp32s2)))]
+    /// Reads the bytes that are available in the RX FIFO into `buffer`, and
+    /// returns their number.
+    fn read_available_from_fifo(&self, buffer: &mut [u8]) -> usize {
+        let mut count = 0;
+        while count < buffer.len() {
+            let reg = self.regs().fifo_st().read();
+            if reg.rxfifo_raddr().bits() == reg.rxfifo_waddr().bits() {
+                break;
+            }
+
+            buffer[count] = read_fifo(self.regs());
+            count += 1;
+        }
+        count
+    }
+
+    #[cfg(any(esp32, esp32s2))]
+    /// Reads the bytes that are available in the RX FIFO into `buffer`, and
+    /// returns their number.
+    ///
+    /// Must only be called after the read has completed, at which point the
+    /// FIFO holds all bytes.
+    fn read_available_from_fifo(&self, buffer: &mut [u8]) -> usize {
+        for byte in buffer.iter_mut() {
+            *byte = read_fifo(self.regs());
+        }
+        buffer.len()
+    }
+
     /// Checks for I2C transmission errors and handles them.
     ///
     /// This function inspects specific I2C-related interrupts to detect errors
@@ -1985,9 +5935,18 @@ impl Driver<'_> {
         // scheme!
         cfg_if::cfg_if! {
             if #[cfg(esp32)] {
+                // The ESP32 has no `resp_rec` status bit. Its `ack_rec` bit holds the
+                // last ACK bit seen on the bus, including the NACK the master sends
+                // itself after the last byte of a read, so it can't be used to detect
+                // a data NACK after the fact. This isn't needed though: every write
+                // command that checks the ACK raises the `nack` interrupt handled
+                // below on a NACK from the slave. The reason for the
+                // failure can't be determined on the ESP32 and is reported as
+                // `AcknowledgeCheckFailedReason::Unknown`.
+
                 // Handle error cases
                 let retval = if interrupts.time_out().bit_is_set() {
-                    Err(Error::Timeout)
+                    Err(timeout_error(self.state))
                 } else if interrupts.nack().bit_is_set() {
                     Err(Error::AcknowledgeCheckFailed(estimate_ack_failed_reason(self.regs())))
                 } else if interrupts.arbitration_lost().bit_is_set() {
@@ -1998,12 +5957,12 @@ impl Driver<'_> {
             } else {
                 // Handle error cases
                 let retval = if interrupts.time_out().bit_is_set() {
-                    Err(Error::Timeout)
+                    Err(timeout_error(self.state))
                 } else if interrupts.nack().bit_is_set() {
                     Err(Error::AcknowledgeCheckFailed(estimate_ack_failed_reason(self.regs())))
                 } else if interrupts.arbitration_lost().bit_is_set() {
                     Err(Error::ArbitrationLost)
-                } else if interrupts.trans_complete().bit_is_set() && self.regs().sr().read().resp_rec().bit_is_clear() {
+                } else if self.check_data_ack && interrupts.trans_complete().bit_is_set() && self.regs().sr().read().resp_rec().bit_is_clear() {
                     Err(Error::AcknowledgeCheckFailed(AcknowledgeCheckFailedReason::Data))
                 } else {
                     Ok(())
@@ -2011,7 +5970,14 @@ impl Driver<'_> {
             }
         }
 
+        if retval == Err(Error::ArbitrationLost) {
+            self.state
+                .arbitration_losses
+                .fetch_add(1, Ordering::Relaxed);
+        }
+
         if retval.is_err() {
+            BusErrorFlags::record(self.regs(), self.state);
             self.reset();
         }
 
@@ -2071,6 +6037,7 @@ impl Driver<'_> {
 
             while !self.regs().int_raw().read().txfifo_wm().bit_is_set() {
                 self.check_errors()?;
+                self.wait_for_event(true);
             }
 
             self.regs()
@@ -2079,6 +6046,7 @@ impl Driver<'_> {
 
             while !self.regs().int_raw().read().txfifo_wm().bit_is_set() {
                 self.check_errors()?;
+                self.wait_for_event(true);
             }
 
             if index >= bytes.len() {
@@ -2097,7 +6065,7 @@ impl Driver<'_> {
         // FIFO apparently it would be possible by using non-fifo mode
         // see  https://github.com/espressif/arduino-esp32/blob/7e9afe8c5ed7b5bf29624a5cd6e07d431c027b97/cores/esp32/esp32-hal-i2c.c#L615
 
-        if bytes.len() > 31 {
+        if bytes.len() > I2C_WRITE_CHUNK_SIZE {
             return Err(Error::FifoExceeded);
         }
 
@@ -2189,8 +6157,8 @@ impl Driver<'_> {
         start: bool,
         stop: bool,
     ) -> Result<usize, Error> {
-        self.reset_fifo();
-        self.reset_command_list();
+        self.check_address(address)?;
+        self.set_current_address(address);
         let cmd_iterator = &mut self.regs().comd_iter();
 
         if start {
@@ -2209,26 +6177,208 @@ impl Driver<'_> {
         Ok(index)
     }
 
+    /// Starts sending a START condition, `raw_byte` and a STOP condition.
+    /// - `raw_byte` is written to the bus as-is, in place of the address byte
+    ///   made of a 7-bit address and the R/W bit.
+    fn start_raw_address_operation(&self, raw_byte: u8) -> Result<(), Error> {
+        // Report the address the byte would select in a regular transfer
+        self.set_current_address(I2cAddress::SevenBit(raw_byte >> 1));
+
+        let cmd_iterator = &mut self.regs().comd_iter();
+
+        add_cmd(cmd_iterator, Command::Start)?;
+        add_cmd(
+            cmd_iterator,
+            Command::Write {
+                ack_exp: Ack::Ack,
+                ack_check_en: true,
+                length: 1,
+            },
+        )?;
+        add_cmd(cmd_iterator, Command::Stop)?;
+
+        self.update_config();
+
+        // Load the byte as-is into FIFO
+        write_fifo(self.regs(), raw_byte);
+
+        self.start_transmission();
+        Ok(())
+    }
+
+    /// Sends a START condition, `raw_byte` and a STOP condition.
+    fn send_raw_address_blocking(&self, raw_byte: u8) -> Result<(), Error> {
+        self.begin_transaction_blocking()?;
+        self.clear_all_interrupts();
+        self.start_raw_address_operation(raw_byte)?;
+        self.wait_for_completion_blocking()?;
+        Ok(())
+    }
+
+    /// Sends a START condition, `raw_byte` and a STOP condition.
+    async fn send_raw_address(&self, raw_byte: u8) -> Result<(), Error> {
+        self.begin_transaction().await?;
+        self.clear_all_interrupts();
+        self.start_raw_address_operation(raw_byte)?;
+        self.wait_for_completion().await?;
+        Ok(())
+    }
+
+    /// Starts a command sequence that only consists of a STOP condition.
+    fn start_stop_operation(&self) -> Result<(), Error> {
+        self.reset_fifo();
+        self.reset_command_list();
+        add_cmd(&mut self.regs().comd_iter(), Command::Stop)?;
+        self.update_config();
+        self.start_transmission();
+        Ok(())
+    }
+
+    /// Sends a STOP condition if the bus is busy.
+    fn send_stop_blocking(&self) -> Result<(), Error> {
+        self.state.bus_held.store(false, Ordering::Relaxed);
+        if !self.is_bus_busy() {
+            return Ok(());
+        }
+
+        self.clear_all_interrupts();
+        self.start_stop_operation()?;
+        self.wait_for_completion_blocking()
+    }
+
+    /// Sends a STOP condition if the bus is busy.
+    async fn send_stop(&self) -> Result<(), Error> {
+        self.state.bus_held.store(false, Ordering::Relaxed);
+        if !self.is_bus_busy() {
+            return Ok(());
+        }
+
+        self.clear_all_interrupts();
+        self.start_stop_operation()?;
+        self.wait_for_completion().await
+    }
+
+    /// Executes an SMBus quick command.
+    fn smbus_quick_blocking(&self, address: I2cAddress, value: bool) -> Result<(), Error> {
+        self.check_address(address)?;
+        // The quick command bit takes the place of the R/W bit
+        match address {
+            I2cAddress::SevenBit(addr) => self.send_raw_address_blocking((addr << 1) | value as u8),
+        }
+    }
+
+    /// Executes an async SMBus quick command.
+    async fn smbus_quick(&self, address: I2cAddress, value: bool) -> Result<(), Error> {
+        self.check_address(address)?;
+        // The quick command bit takes the place of the R/W bit
+        match address {
+            I2cAddress::SevenBit(addr) => self.send_raw_address((addr << 1) | value as u8).await,
+        }
+    }
+
+    /// Executes an SMBus block read.
+    ///
+    /// The count byte is read as a separate operation that ACKs the byte and
+    /// ends with END, so that the length of the following read can be
+    /// decided after it has been received.
+    fn smbus_block_read_blocking(
+        &self,
+        address: I2cAddress,
+        command: u8,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.begin_transaction_blocking()?;
+        self.write_blocking(address, &[command], true, false)?;
+
+        let mut count = [0u8; 1];
+        self.read_blocking(address, &mut count, true, false, true)?;
+
+        let len = out.len().min(count[0] as usize);
+        if len == 0 {
+            // The device has no data, just release the bus
+            self.write_operation_blocking(address, &[], false, true)?;
+        } else {
+            self.read_blocking(address, &mut out[..len], false, true, false)?;
+        }
+
+        Ok(len)
+    }
+
+    /// Executes an async SMBus block read.
+    async fn smbus_block_read(
+        &self,
+        address: I2cAddress,
+        command: u8,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.begin_transaction().await?;
+        self.write(address, &[command], true, false).await?;
+
+        let mut count = [0u8; 1];
+        self.read(address, &mut count, true, false, true).await?;
+
+        let len = out.len().min(count[0] as usize);
+        if len == 0 {
+            // The device has no data, just release the bus
+            self.write_operation(address, &[], false, true).await?;
+        } else {
+            self.read(address, &mut out[..len], false, true, false)
+                .await?;
+        }
+
+        Ok(len)
+    }
+
+    /// Executes two writes and a read, each starting with a (repeated) START.
+    fn write_then_write_read_blocking(
+        &self,
+        address: I2cAddress,
+        first: &[u8],
+        second: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.begin_transaction_blocking()?;
+        self.write_blocking(address, first, true, false)?;
+        self.write_blocking(address, second, true, false)?;
+        self.read_blocking(address, read_buffer, true, true, false)
+    }
+
+    /// Executes two writes and a read, each starting with a (repeated) START.
+    async fn write_then_write_read(
+        &self,
+        address: I2cAddress,
+        first: &[u8],
+        second: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.begin_transaction().await?;
+        self.write(address, first, true, false).await?;
+        self.write(address, second, true, false).await?;
+        self.read(address, read_buffer, true, true, false).await
+    }
+
     /// Executes an I2C read operation.
     /// - `addr` is the address of the slave device.
-    /// - `buffer` is the buffer to store the read data.
+    /// - `len` is the number of bytes to read.
     /// - `start` indicates whether the operation should start by a START
     ///   condition and sending the address.
     /// - `stop` indicates whether the operation should end with a STOP
     ///   condition.
     /// - `will_continue` indicates whether there is another read operation
     ///   following this one and we should not nack the last byte.
-    /// - `cmd_iterator` is an iterator over the command registers.
+    /// - `last_ack` is the ACK value of the last byte, if the read doesn't
+    ///   continue.
     fn start_read_operation(
         &self,
         address: I2cAddress,
-        buffer: &mut [u8],
+        len: usize,
         start: bool,
         stop: bool,
         will_continue: bool,
+        last_ack: Ack,
     ) -> Result<(), Error> {
-        self.reset_fifo();
-        self.reset_command_list();
+        self.check_address(address)?;
+        self.set_current_address(address);
 
         let cmd_iterator = &mut self.regs().comd_iter();
 
@@ -2236,7 +6386,7 @@ impl Driver<'_> {
             add_cmd(cmd_iterator, Command::Start)?;
         }
 
-        self.setup_read(address, buffer, start, will_continue, cmd_iterator)?;
+        self.setup_read(address, len, start, will_continue, last_ack, cmd_iterator)?;
 
         add_cmd(
             cmd_iterator,
@@ -2272,69 +6422,199 @@ impl Driver<'_> {
         let index = self.start_write_operation(address, bytes, start, stop)?;
         // Fill the FIFO with the remaining bytes:
         self.write_remaining_tx_fifo_blocking(index, bytes)?;
-        self.wait_for_completion_blocking(!stop)?;
+        self.wait_for_completion_blocking()?;
+        Ok(())
+    }
+
+    /// Executes an I2C read operation.
+    /// - `addr` is the address of the slave device.
+    /// - `buffer` is the buffer to store the read data.
+    /// - `start` indicates whether the operation should start by a START
+    ///   condition and sending the address.
+    /// - `stop` indicates whether the operation should end with a STOP
+    ///   condition.
+    /// - `will_continue` indicates whether there is another read operation
+    ///   following this one and we should not nack the last byte.
+    /// - `last_ack` is the ACK value of the last byte, if the read doesn't
+    ///   continue.
+    fn read_operation_blocking(
+        &self,
+        address: I2cAddress,
+        buffer: &mut [u8],
+        start: bool,
+        stop: bool,
+        will_continue: bool,
+        last_ack: Ack,
+    ) -> Result<(), Error> {
+        self.clear_all_interrupts();
+
+        // Short circuit for zero length reads as that would be an invalid operation
+        // read lengths in the TRM (at least for ESP32-S3) are 1-255
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        self.start_read_operation(address, buffer.len(), start, stop, will_continue, last_ack)?;
+        self.read_all_from_fifo_blocking(buffer)?;
+        self.wait_for_completion_blocking()?;
+        Ok(())
+    }
+
+    /// Executes an async I2C write operation.
+    /// - `addr` is the address of the slave device.
+    /// - `bytes` is the data two be sent.
+    /// - `start` indicates whether the operation should start by a START
+    ///   condition and sending the address.
+    /// - `stop` indicates whether the operation should end with a STOP
+    ///   condition.
+    /// - `cmd_iterator` is an iterator over the command registers.
+    async fn write_operation(
+        &self,
+        address: I2cAddress,
+        bytes: &[u8],
+        start: bool,
+        stop: bool,
+    ) -> Result<(), Error> {
+        self.clear_all_interrupts();
+
+        // Short circuit for zero length writes without start or end as that would be an
+        // invalid operation write lengths in the TRM (at least for ESP32-S3) are 1-255
+        if bytes.is_empty() && !start && !stop {
+            return Ok(());
+        }
+
+        let index = self.start_write_operation(address, bytes, start, stop)?;
+        // Fill the FIFO with the remaining bytes:
+        self.write_remaining_tx_fifo(index, bytes).await?;
+        self.wait_for_completion().await?;
+        Ok(())
+    }
+
+    /// Executes an I2C read operation, passing the received bytes to `sink` as
+    /// they arrive.
+    /// - `addr` is the address of the slave device.
+    /// - `len` is the number of bytes to read.
+    /// - `start` indicates whether the operation should start by a START
+    ///   condition and sending the address.
+    /// - `stop` indicates whether the operation should end with a STOP
+    ///   condition.
+    /// - `will_continue` indicates whether there is another read operation
+    ///   following this one and we should not nack the last byte.
+    fn read_stream_operation_blocking(
+        &self,
+        address: I2cAddress,
+        len: usize,
+        start: bool,
+        stop: bool,
+        will_continue: bool,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
+        self.clear_all_interrupts();
+
+        self.start_read_operation(address, len, start, stop, will_continue, Ack::Nack)?;
+        self.stream_from_fifo_blocking(len, sink)?;
+        self.wait_for_completion_blocking()?;
         Ok(())
     }
 
-    /// Executes an I2C read operation.
+    /// Executes an async I2C read operation, passing the received bytes to
+    /// `sink` as they arrive.
     /// - `addr` is the address of the slave device.
-    /// - `buffer` is the buffer to store the read data.
+    /// - `len` is the number of bytes to read.
     /// - `start` indicates whether the operation should start by a START
     ///   condition and sending the address.
     /// - `stop` indicates whether the operation should end with a STOP
     ///   condition.
     /// - `will_continue` indicates whether there is another read operation
     ///   following this one and we should not nack the last byte.
-    /// - `cmd_iterator` is an iterator over the command registers.
-    fn read_operation_blocking(
+    async fn read_stream_operation(
         &self,
         address: I2cAddress,
-        buffer: &mut [u8],
+        len: usize,
         start: bool,
         stop: bool,
         will_continue: bool,
+        sink: &mut impl FnMut(u8),
     ) -> Result<(), Error> {
         self.clear_all_interrupts();
 
-        // Short circuit for zero length reads as that would be an invalid operation
-        // read lengths in the TRM (at least for ESP32-S3) are 1-255
-        if buffer.is_empty() {
-            return Ok(());
+        self.start_read_operation(address, len, start, stop, will_continue, Ack::Nack)?;
+        self.stream_from_fifo(len, stop, sink).await?;
+        self.wait_for_completion().await?;
+        Ok(())
+    }
+
+    #[cfg(not(any(esp32, esp32s2)))]
+    /// Passes `len` bytes from the RX FIFO to `sink`, waiting for the RX
+    /// watermark interrupt whenever the FIFO runs empty.
+    async fn stream_from_fifo(
+        &self,
+        len: usize,
+        stop: bool,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
+        for _ in 0..len {
+            loop {
+                self.check_errors()?;
+
+                // Clear the watermark before checking the FIFO, so a byte arriving in
+                // between raises the interrupt again.
+                self.regs()
+                    .int_clr()
+                    .write(|w| w.rxfifo_wm().clear_bit_by_one());
+
+                let reg = self.regs().fifo_st().read();
+                if reg.rxfifo_raddr().bits() != reg.rxfifo_waddr().bits() {
+                    break;
+                }
+
+                // The watermark may not trigger for the last few bytes, but by then the
+                // operation completes and they are already in the FIFO.
+                let res = embassy_futures::select::select(
+                    I2cFuture::new(Event::RxFifoWatermark, self),
+                    I2cFuture::new(
+                        if stop {
+                            Event::TxComplete
+                        } else {
+                            Event::EndDetect
+                        },
+                        self,
+                    ),
+                )
+                .await;
+
+                match res {
+                    embassy_futures::select::Either::First(res) => res?,
+                    embassy_futures::select::Either::Second(res) => res?,
+                }
+            }
+
+            sink(read_fifo(self.regs()));
         }
 
-        self.start_read_operation(address, buffer, start, stop, will_continue)?;
-        self.read_all_from_fifo_blocking(buffer)?;
-        self.wait_for_completion_blocking(!stop)?;
         Ok(())
     }
 
-    /// Executes an async I2C write operation.
-    /// - `addr` is the address of the slave device.
-    /// - `bytes` is the data two be sent.
-    /// - `start` indicates whether the operation should start by a START
-    ///   condition and sending the address.
-    /// - `stop` indicates whether the operation should end with a STOP
-    ///   condition.
-    /// - `cmd_iterator` is an iterator over the command registers.
-    async fn write_operation(
+    #[cfg(any(esp32, esp32s2))]
+    /// Passes `len` bytes from the RX FIFO to `sink`.
+    async fn stream_from_fifo(
         &self,
-        address: I2cAddress,
-        bytes: &[u8],
-        start: bool,
-        stop: bool,
+        len: usize,
+        _stop: bool,
+        sink: &mut impl FnMut(u8),
     ) -> Result<(), Error> {
-        self.clear_all_interrupts();
+        // Like `read_all_from_fifo`, the data can only be read once the operation
+        // has completed.
+        if len > 32 {
+            return Err(Error::FifoExceeded);
+        }
 
-        // Short circuit for zero length writes without start or end as that would be an
-        // invalid operation write lengths in the TRM (at least for ESP32-S3) are 1-255
-        if bytes.is_empty() && !start && !stop {
-            return Ok(());
+        self.wait_for_completion().await?;
+
+        for _ in 0..len {
+            sink(read_fifo(self.regs()));
         }
 
-        let index = self.start_write_operation(address, bytes, start, stop)?;
-        // Fill the FIFO with the remaining bytes:
-        self.write_remaining_tx_fifo(index, bytes).await?;
-        self.wait_for_completion(!stop).await?;
         Ok(())
     }
 
@@ -2347,7 +6627,8 @@ impl Driver<'_> {
     ///   condition.
     /// - `will_continue` indicates whether there is another read operation
     ///   following this one and we should not nack the last byte.
-    /// - `cmd_iterator` is an iterator over the command registers.
+    /// - `last_ack` is the ACK value of the last byte, if the read doesn't
+    ///   continue.
     async fn read_operation(
         &self,
         address: I2cAddress,
@@ -2355,6 +6636,7 @@ impl Driver<'_> {
         start: bool,
         stop: bool,
         will_continue: bool,
+        last_ack: Ack,
     ) -> Result<(), Error> {
         self.clear_all_interrupts();
 
@@ -2364,9 +6646,9 @@ impl Driver<'_> {
             return Ok(());
         }
 
-        self.start_read_operation(address, buffer, start, stop, will_continue)?;
+        self.start_read_operation(address, buffer.len(), start, stop, will_continue, last_ack)?;
         self.read_all_from_fifo(buffer).await?;
-        self.wait_for_completion(!stop).await?;
+        self.wait_for_completion().await?;
         Ok(())
     }
 
@@ -2378,15 +6660,34 @@ impl Driver<'_> {
         stop: bool,
         will_continue: bool,
     ) -> Result<(), Error> {
-        let chunk_count = buffer.len().div_ceil(I2C_CHUNK_SIZE);
-        for (idx, chunk) in buffer.chunks_mut(I2C_CHUNK_SIZE).enumerate() {
+        self.read_blocking_counted(address, buffer, start, stop, will_continue, &mut 0)
+    }
+
+    /// Like [`Self::read_blocking`], but adds the number of bytes of the
+    /// chunks that were read successfully to `transferred`.
+    fn read_blocking_counted(
+        &self,
+        address: I2cAddress,
+        buffer: &mut [u8],
+        start: bool,
+        stop: bool,
+        will_continue: bool,
+        transferred: &mut usize,
+    ) -> Result<(), Error> {
+        // Only the first chunk sends START and the address, the following chunks
+        // continue the same read. All chunks but the last ACK their final byte.
+        let len = buffer.len();
+        for chunk in read_chunks(len, will_continue) {
+            let last = chunk.end == len;
             self.read_operation_blocking(
                 address,
-                chunk,
-                start && idx == 0,
-                stop && idx == chunk_count - 1,
-                will_continue || idx < chunk_count - 1,
+                &mut buffer[chunk.clone()],
+                start && chunk.start == 0,
+                stop && last,
+                will_continue || !last,
+                Ack::Nack,
             )?;
+            *transferred += chunk.len();
         }
 
         Ok(())
@@ -2398,18 +6699,31 @@ impl Driver<'_> {
         buffer: &[u8],
         start: bool,
         stop: bool,
+    ) -> Result<(), Error> {
+        self.write_blocking_counted(address, buffer, start, stop, &mut 0)
+    }
+
+    /// Like [`Self::write_blocking`], but adds the number of bytes of the
+    /// chunks that were written successfully to `transferred`.
+    fn write_blocking_counted(
+        &self,
+        address: I2cAddress,
+        buffer: &[u8],
+        start: bool,
+        stop: bool,
+        transferred: &mut usize,
     ) -> Result<(), Error> {
         if buffer.is_empty() {
             return self.write_operation_blocking(address, &[], start, stop);
         }
-        let chunk_count = buffer.len().div_ceil(I2C_CHUNK_SIZE);
-        for (idx, chunk) in buffer.chunks(I2C_CHUNK_SIZE).enumerate() {
+        for chunk in write_chunks(buffer.len(), start) {
             self.write_operation_blocking(
                 address,
-                chunk,
-                start && idx == 0,
-                stop && idx == chunk_count - 1,
+                &buffer[chunk.clone()],
+                start && chunk.start == 0,
+                stop && chunk.end == buffer.len(),
             )?;
+            *transferred += chunk.len();
         }
 
         Ok(())
@@ -2423,14 +6737,127 @@ impl Driver<'_> {
         stop: bool,
         will_continue: bool,
     ) -> Result<(), Error> {
-        let chunk_count = buffer.len().div_ceil(I2C_CHUNK_SIZE);
-        for (idx, chunk) in buffer.chunks_mut(I2C_CHUNK_SIZE).enumerate() {
+        self.read_counted(address, buffer, start, stop, will_continue, &mut 0)
+            .await
+    }
+
+    /// Like [`Self::read`], but adds the number of bytes of the chunks that
+    /// were read successfully to `transferred`.
+    async fn read_counted(
+        &self,
+        address: I2cAddress,
+        buffer: &mut [u8],
+        start: bool,
+        stop: bool,
+        will_continue: bool,
+        transferred: &mut usize,
+    ) -> Result<(), Error> {
+        // Only the first chunk sends START and the address, the following chunks
+        // continue the same read. All chunks but the last ACK their final byte.
+        let len = buffer.len();
+        for chunk in read_chunks(len, will_continue) {
+            let last = chunk.end == len;
+            self.read_operation(
+                address,
+                &mut buffer[chunk.clone()],
+                start && chunk.start == 0,
+                stop && last,
+                will_continue || !last,
+                Ack::Nack,
+            )
+            .await?;
+            *transferred += chunk.len();
+        }
+
+        Ok(())
+    }
+
+    fn read_stream_blocking(
+        &self,
+        address: I2cAddress,
+        count: usize,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
+        self.begin_transaction_blocking()?;
+
+        for chunk in read_chunks(count, false) {
+            let last = chunk.end == count;
+            self.read_stream_operation_blocking(
+                address,
+                chunk.len(),
+                chunk.start == 0,
+                last,
+                !last,
+                sink,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    async fn read_stream(
+        &self,
+        address: I2cAddress,
+        count: usize,
+        sink: &mut impl FnMut(u8),
+    ) -> Result<(), Error> {
+        self.begin_transaction().await?;
+
+        for chunk in read_chunks(count, false) {
+            let last = chunk.end == count;
+            self.read_stream_operation(address, chunk.len(), chunk.start == 0, last, !last, sink)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads `buffer` in a transaction of its own, ending with a STOP
+    /// condition, and answers its final byte with `last_ack`.
+    fn read_with_last_ack_blocking(
+        &self,
+        address: I2cAddress,
+        buffer: &mut [u8],
+        last_ack: Ack,
+    ) -> Result<(), Error> {
+        self.begin_transaction_blocking()?;
+
+        let len = buffer.len();
+        for chunk in read_chunks(len, false) {
+            let last = chunk.end == len;
+            self.read_operation_blocking(
+                address,
+                &mut buffer[chunk.clone()],
+                chunk.start == 0,
+                last,
+                !last,
+                last_ack,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads `buffer` in a transaction of its own, like
+    /// `read_with_last_ack_blocking`.
+    async fn read_with_last_ack(
+        &self,
+        address: I2cAddress,
+        buffer: &mut [u8],
+        last_ack: Ack,
+    ) -> Result<(), Error> {
+        self.begin_transaction().await?;
+
+        let len = buffer.len();
+        for chunk in read_chunks(len, false) {
+            let last = chunk.end == len;
             self.read_operation(
                 address,
-                chunk,
-                start && idx == 0,
-                stop && idx == chunk_count - 1,
-                will_continue || idx < chunk_count - 1,
+                &mut buffer[chunk.clone()],
+                chunk.start == 0,
+                last,
+                !last,
+                last_ack,
             )
             .await?;
         }
@@ -2444,23 +6871,220 @@ impl Driver<'_> {
         buffer: &[u8],
         start: bool,
         stop: bool,
+    ) -> Result<(), Error> {
+        self.write_counted(address, buffer, start, stop, &mut 0)
+            .await
+    }
+
+    /// Like [`Self::write`], but adds the number of bytes of the chunks that
+    /// were written successfully to `transferred`.
+    async fn write_counted(
+        &self,
+        address: I2cAddress,
+        buffer: &[u8],
+        start: bool,
+        stop: bool,
+        transferred: &mut usize,
     ) -> Result<(), Error> {
         if buffer.is_empty() {
             return self.write_operation(address, &[], start, stop).await;
         }
-        let chunk_count = buffer.len().div_ceil(I2C_CHUNK_SIZE);
-        for (idx, chunk) in buffer.chunks(I2C_CHUNK_SIZE).enumerate() {
+        for chunk in write_chunks(buffer.len(), start) {
             self.write_operation(
                 address,
-                chunk,
-                start && idx == 0,
-                stop && idx == chunk_count - 1,
+                &buffer[chunk.clone()],
+                start && chunk.start == 0,
+                stop && chunk.end == buffer.len(),
             )
             .await?;
+            *transferred += chunk.len();
         }
 
         Ok(())
     }
+
+    /// Writes the bytes produced by `bytes`, in chunks that each fit into one
+    /// WRITE command.
+    ///
+    /// The length of a WRITE command has to be known before it is started, so
+    /// each chunk is collected first. Within a chunk, the TX FIFO is refilled
+    /// as it drains by `write_operation_blocking`. Every chunk but the last
+    /// ends with an END command, so the next one continues the same transfer.
+    fn write_iter_blocking(
+        &self,
+        address: I2cAddress,
+        bytes: impl Iterator<Item = u8>,
+    ) -> Result<(), Error> {
+        self.begin_transaction_blocking()?;
+
+        let mut bytes = bytes.peekable();
+        let mut chunk = [0u8; I2C_WRITE_CHUNK_SIZE];
+        let mut start = true;
+        loop {
+            let mut len = 0;
+            let max_len = max_write_chunk_len(start);
+            for (slot, byte) in chunk[..max_len].iter_mut().zip(&mut bytes) {
+                *slot = byte;
+                len += 1;
+            }
+
+            let stop = bytes.peek().is_none();
+            self.write_operation_blocking(address, &chunk[..len], start, stop)?;
+            if stop {
+                return Ok(());
+            }
+
+            start = false;
+        }
+    }
+
+    /// Writes the bytes produced by `bytes`, like `write_iter_blocking`.
+    async fn write_iter(
+        &self,
+        address: I2cAddress,
+        bytes: impl Iterator<Item = u8>,
+    ) -> Result<(), Error> {
+        self.begin_transaction().await?;
+
+        let mut bytes = bytes.peekable();
+        let mut chunk = [0u8; I2C_WRITE_CHUNK_SIZE];
+        let mut start = true;
+        loop {
+            let mut len = 0;
+            let max_len = max_write_chunk_len(start);
+            for (slot, byte) in chunk[..max_len].iter_mut().zip(&mut bytes) {
+                *slot = byte;
+                len += 1;
+            }
+
+            let stop = bytes.peek().is_none();
+            self.write_operation(address, &chunk[..len], start, stop)
+                .await?;
+            if stop {
+                return Ok(());
+            }
+
+            start = false;
+        }
+    }
+}
+
+/// Returns the maximum length of a write chunk.
+///
+/// A chunk that starts with START also carries the address in its WRITE
+/// command, which leaves one byte less for the data. On the ESP32 and ESP32-S2
+/// the address byte is already accounted for by `I2C_WRITE_CHUNK_SIZE`.
+fn max_write_chunk_len(start: bool) -> usize {
+    if start && cfg!(not(any(esp32, esp32s2))) {
+        I2C_WRITE_CHUNK_SIZE - 1
+    } else {
+        I2C_WRITE_CHUNK_SIZE
+    }
+}
+
+/// Splits a write of `len` bytes into the byte ranges of its chunks.
+fn write_chunks(len: usize, start: bool) -> impl Iterator<Item = Range<usize>> {
+    let mut offset = 0;
+    core::iter::from_fn(move || {
+        if offset == len {
+            return None;
+        }
+
+        let chunk_len = (len - offset).min(max_write_chunk_len(start && offset == 0));
+        let chunk = offset..offset + chunk_len;
+        offset = chunk.end;
+        Some(chunk)
+    })
+}
+
+/// Splits a read of `len` bytes into the byte ranges of its chunks.
+///
+/// Unless the read continues with another read operation, the last chunk
+/// answers its final byte with a separate READ command, which leaves one byte
+/// less for it. This doesn't depend on whether that byte is NACKed or ACKed.
+/// All other chunks can use the full command length.
+fn read_chunks(len: usize, will_continue: bool) -> impl Iterator<Item = Range<usize>> {
+    let mut offset = 0;
+    core::iter::from_fn(move || {
+        let remaining = len - offset;
+        if remaining == 0 {
+            return None;
+        }
+
+        let mut chunk_len = remaining.min(I2C_READ_CHUNK_SIZE);
+        if cfg!(not(any(esp32, esp32s2)))
+            && !will_continue
+            && chunk_len == remaining
+            && chunk_len == I2C_READ_CHUNK_SIZE
+        {
+            chunk_len -= 1;
+        }
+
+        let chunk = offset..offset + chunk_len;
+        offset = chunk.end;
+        Some(chunk)
+    })
+}
+
+fn check_frequency(bus_freq: u32) -> Result<u32, ConfigError> {
+    if (1..=I2C_MAX_FREQUENCY).contains(&bus_freq) {
+        Ok(bus_freq)
+    } else {
+        Err(ConfigError::FrequencyInvalid)
+    }
+}
+
+/// Splits the SCL period into the low and high time, in source clock cycles.
+///
+/// Without a duty cycle, both take `half_cycle`. Otherwise the resulting times
+/// are checked against the minimum SCL low and high times of the I2C
+/// specification for the speed mode `bus_freq` falls into.
+fn scl_duty_cycle(
+    duty_cycle: Option<u8>,
+    bus_freq: u32,
+    half_cycle: u32,
+) -> Result<(u32, u32), ConfigError> {
+    let Some(duty_cycle) = duty_cycle else {
+        return Ok((half_cycle, half_cycle));
+    };
+
+    // Minimum (t_LOW, t_HIGH) in nanoseconds
+    let (min_low, min_high) = match bus_freq {
+        0..=100_000 => (4700, 4000),
+        100_001..=400_000 => (1300, 600),
+        _ => (500, 260),
+    };
+
+    let period = 1_000_000_000 / bus_freq;
+    let low = period * duty_cycle as u32 / 100;
+    if duty_cycle >= 100 || low < min_low || period - low < min_high {
+        return Err(ConfigError::DutyCycleInvalid);
+    }
+
+    let cycle = 2 * half_cycle;
+    let scl_low = cycle * duty_cycle as u32 / 100;
+    Ok((scl_low, cycle - scl_low))
+}
+
+fn timing_override(value: Option<u16>, computed: u32) -> Result<u32, ConfigError> {
+    match value {
+        Some(value) if value as u32 <= MAX_TIMING_CYCLES => Ok(value as u32),
+        Some(_) => Err(ConfigError::TimingInvalid),
+        None => Ok(computed),
+    }
+}
+
+/// Checks a [`Config::scl_wait_high`] override against the SCL high period
+/// `high_cycle` it is split off from.
+#[cfg(not(esp32))]
+fn check_scl_wait_high(value: u16, high_cycle: u32) -> Result<u32, ConfigError> {
+    let value = value as u32;
+    // scl_wait_high < scl_high, where scl_high = high_cycle - scl_wait_high
+    if value > MAX_SCL_WAIT_HIGH || 2 * value >= high_cycle {
+        return Err(ConfigError::FrequencyInvalid);
+    }
+
+    Ok(value)
 }
 
 fn check_timeout(v: u32, max: u32) -> Result<u32, ConfigError> {
@@ -2477,23 +7101,81 @@ fn check_timeout(v: u32, max: u32) -> Result<u32, ConfigError> {
 pub struct State {
     /// Waker for the asynchronous operations.
     pub waker: AtomicWaker,
+
+    /// Number of times the arbitration for the bus was lost.
+    pub arbitration_losses: AtomicU32,
+
+    /// Address of the device the current operation talks to, or
+    /// `NO_ADDRESS`.
+    pub current_address: AtomicU8,
+
+    /// Address of the device the last failed operation talked to, or
+    /// `NO_ADDRESS`.
+    pub last_error_address: AtomicU8,
+
+    /// Number of the open drain pin connected to SCL, or `NO_PIN`.
+    pub scl_pin: AtomicU8,
+
+    /// Numbers of the output and input pin connected to SDA, or `NO_PIN`.
+    pub sda_pins: [AtomicU8; 2],
+
+    /// Numbers of the output and input pin connected to SCL, or `NO_PIN`.
+    pub scl_pins: [AtomicU8; 2],
+
+    /// Whether SCL is inverted between the peripheral and its pin.
+    pub scl_inverted: AtomicBool,
+
+    /// Whether the last transaction left the bus held.
+    pub bus_held: AtomicBool,
+
+    /// The error flags seen when the current transaction failed.
+    pub last_error_flags: AtomicU8,
+
+    /// When the interrupt handler last saw a command sequence complete.
+    last_completion: Locked<Option<Instant>>,
+}
+
+/// Marks the address fields of [`State`] as empty. Not a valid 7-bit address.
+const NO_ADDRESS: u8 = u8::MAX;
+
+/// Marks the pin fields of [`State`] as empty.
+const NO_PIN: u8 = u8::MAX;
+
+/// Returns the numbers of the pins recorded in `pins`.
+fn recorded_pins(pins: &[AtomicU8; 2]) -> impl Iterator<Item = u8> + '_ {
+    pins.iter()
+        .map(|pin| pin.load(Ordering::Relaxed))
+        .filter(|pin| *pin != NO_PIN)
+}
+
+impl State {
+    /// Returns the numbers of the pins connected to SDA or SCL.
+    fn connected_pins(&self) -> impl Iterator<Item = u8> + '_ {
+        recorded_pins(&self.sda_pins).chain(recorded_pins(&self.scl_pins))
+    }
+
+    fn clear_pins(&self) {
+        for pin in self.sda_pins.iter().chain(&self.scl_pins) {
+            pin.store(NO_PIN, Ordering::Relaxed);
+        }
+    }
 }
 
 /// I2C Peripheral Instance
 #[doc(hidden)]
 pub trait Instance: Peripheral<P = Self> + Into<AnyI2c> + 'static {
     /// Returns the peripheral data and state describing this instance.
-    fn parts(&self) -> (&Info, &State);
+    fn parts(&self) -> (&'static Info, &'static State);
 
     /// Returns the peripheral data describing this instance.
     #[inline(always)]
-    fn info(&self) -> &Info {
+    fn info(&self) -> &'static Info {
         self.parts().0
     }
 
     /// Returns the peripheral state for this instance.
     #[inline(always)]
-    fn state(&self) -> &State {
+    fn state(&self) -> &'static State {
         self.parts().1
     }
 }
@@ -2545,28 +7227,101 @@ fn write_fifo(register_block: &RegisterBlock, data: u8) {
 
 #[cfg(esp32s2)]
 fn read_fifo(register_block: &RegisterBlock) -> u8 {
-    let base_addr = register_block.scl_low_period().as_ptr();
-    let fifo_ptr = (if base_addr as u32 == 0x3f413000 {
-        0x6001301c
-    } else {
-        0x6002701c
-    }) as *mut u32;
+    let fifo_ptr = fifo_ahb_ptr(register_block);
     unsafe { (fifo_ptr.read_volatile() & 0xff) as u8 }
 }
 
 #[cfg(esp32)]
 fn write_fifo(register_block: &RegisterBlock, data: u8) {
-    let base_addr = register_block.scl_low_period().as_ptr();
-    let fifo_ptr = (if base_addr as u32 == 0x3FF53000 {
-        0x6001301c
-    } else {
-        0x6002701c
-    }) as *mut u32;
+    let fifo_ptr = fifo_ahb_ptr(register_block);
     unsafe {
         fifo_ptr.write_volatile(data as u32);
     }
 }
 
+/// Returns the address of the FIFO data register in the AHB address range.
+///
+/// On these chips the FIFO has to be accessed through the AHB address range
+/// instead of the one the register block is mapped to. A peripheral has the
+/// same offset in both ranges.
+#[cfg(any(esp32, esp32s2))]
+fn fifo_ahb_ptr(register_block: &RegisterBlock) -> *mut u32 {
+    cfg_if::cfg_if! {
+        if #[cfg(esp32)] {
+            const PERIPHERAL_BASE: u32 = 0x3FF4_0000;
+        } else {
+            const PERIPHERAL_BASE: u32 = 0x3F40_0000;
+        }
+    }
+    const AHB_BASE: u32 = 0x6000_0000;
+
+    let data_reg = register_block.data().as_ptr() as u32;
+    (data_reg - PERIPHERAL_BASE + AHB_BASE) as *mut u32
+}
+
+/// Returns whether an operation followed by `next_op` ends with a STOP
+/// condition. The last operation of a transaction only does if `stop` is set.
+fn ends_with_stop(next_op: Option<OpKind>, stop: bool) -> bool {
+    match next_op {
+        None => stop,
+        Some(OpKind::Delay) => true,
+        Some(_) => false,
+    }
+}
+
+/// Time for a bus line to settle after it has been released or driven low.
+const LINE_SETTLE_TIME_US: u32 = 10;
+
+/// Checks a bus line for [`I2c::check_bus`].
+///
+/// The pin is temporarily disconnected from the peripheral and driven as a
+/// GPIO, then connected to `signal` again. If the line is `inverted`, the pin
+/// levels are the inverse of the line levels.
+fn check_line(guard: &PinGuard, signal: OutputSignal, inverted: bool) -> Option<LineFault> {
+    let mut pin = unsafe { AnyPin::steal(guard.pin_number()?) };
+    let delay = crate::delay::Delay::new();
+    let is_line_high = |pin: &AnyPin| pin.is_input_high() != inverted;
+
+    let released_high = is_line_high(&pin);
+
+    // Keep the line released while switching over to GPIO control
+    pin.set_output_high(!inverted);
+    OutputSignal::GPIO.connect_to(&mut pin);
+
+    pin.set_output_high(inverted);
+    delay.delay_micros(LINE_SETTLE_TIME_US);
+    let driven_low = !is_line_high(&pin);
+
+    pin.set_output_high(!inverted);
+    delay.delay_micros(LINE_SETTLE_TIME_US);
+    let recovered_high = is_line_high(&pin);
+
+    signal.connect_to(&mut pin);
+    set_output_signal_inverted(pin.number(), inverted);
+
+    if !released_high || !recovered_high {
+        Some(LineFault::NotPulledUp)
+    } else if !driven_low {
+        Some(LineFault::NotDrivenLow)
+    } else {
+        None
+    }
+}
+
+/// Returns the error to report for a bus timeout.
+///
+/// If SCL is still low after the peripheral released it, a slave is
+/// stretching the clock.
+fn timeout_error(state: &State) -> Error {
+    let scl_pin = state.scl_pin.load(Ordering::Relaxed);
+    let scl_inverted = state.scl_inverted.load(Ordering::Relaxed);
+    if scl_pin != NO_PIN && unsafe { AnyPin::steal(scl_pin) }.is_input_high() == scl_inverted {
+        Error::ClockStretchTimeout
+    } else {
+        Error::Timeout
+    }
+}
+
 // Estimate the reason for an acknowledge check failure on a best effort basis.
 // When in doubt it's better to return `Unknown` than to return a wrong reason.
 fn estimate_ack_failed_reason(_register_block: &RegisterBlock) -> AcknowledgeCheckFailedReason {
@@ -2587,7 +7342,7 @@ fn estimate_ack_failed_reason(_register_block: &RegisterBlock) -> AcknowledgeChe
 macro_rules! instance {
     ($inst:ident, $peri:ident, $scl:ident, $sda:ident, $interrupt:ident) => {
         impl Instance for crate::peripherals::$inst {
-            fn parts(&self) -> (&Info, &State) {
+            fn parts(&self) -> (&'static Info, &'static State) {
                 #[crate::handler]
                 pub(super) fn irq_handler() {
                     async_handler(&PERIPHERAL, &STATE);
@@ -2595,6 +7350,16 @@ macro_rules! instance {
 
                 static STATE: State = State {
                     waker: AtomicWaker::new(),
+                    arbitration_losses: AtomicU32::new(0),
+                    current_address: AtomicU8::new(NO_ADDRESS),
+                    last_error_address: AtomicU8::new(NO_ADDRESS),
+                    scl_pin: AtomicU8::new(NO_PIN),
+                    sda_pins: [AtomicU8::new(NO_PIN), AtomicU8::new(NO_PIN)],
+                    scl_pins: [AtomicU8::new(NO_PIN), AtomicU8::new(NO_PIN)],
+                    scl_inverted: AtomicBool::new(false),
+                    bus_held: AtomicBool::new(false),
+                    last_error_flags: AtomicU8::new(0),
+                    last_completion: Locked::new(None),
                 };
 
                 static PERIPHERAL: Info = Info {
@@ -2635,7 +7400,33 @@ impl Instance for AnyI2c {
             #[cfg(i2c1)]
             AnyI2cInner::I2c1(i2c) => i2c,
         } {
-            fn parts(&self) -> (&Info, &State);
+            fn parts(&self) -> (&'static Info, &'static State);
+        }
+    }
+}
+
+impl AnyI2c {
+    /// Conjures the I2C peripheral with the given index out of thin air.
+    ///
+    /// This allows choosing the controller at runtime, e.g. from a
+    /// configuration. Returns `None` if the chip has no I2C peripheral with
+    /// the given index.
+    ///
+    /// If the peripherals are at hand, converting the selected one with
+    /// `.into()` achieves the same without `unsafe`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that only one instance of the peripheral is in
+    /// use at one time.
+    #[instability::unstable]
+    pub unsafe fn steal(index: u8) -> Option<Self> {
+        match index {
+            #[cfg(i2c0)]
+            0 => Some(crate::peripherals::I2C0::steal().into()),
+            #[cfg(i2c1)]
+            1 => Some(crate::peripherals::I2C1::steal().into()),
+            _ => None,
         }
     }
 }
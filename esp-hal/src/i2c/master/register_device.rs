@@ -0,0 +1,115 @@
+//! # 8-bit register access
+//!
+//! Many I2C devices expose their configuration and data as a file of 8-bit
+//! registers, selected by writing the register address before reading or
+//! writing the value. [`RegisterDevice`] wraps an I2C driver and a device
+//! address and provides helpers for this access pattern.
+//!
+//! ## Example
+//!
+//! ```rust, no_run
+#![doc = crate::before_snippet!()]
+//! # use esp_hal::i2c::master::{Config, I2c, register_device::RegisterDevice};
+//! let mut i2c = I2c::new(peripherals.I2C0, Config::default())
+//!     .unwrap()
+//!     .with_sda(peripherals.GPIO1)
+//!     .with_scl(peripherals.GPIO2);
+//! let mut device = RegisterDevice::new(&mut i2c, 0x77);
+//!
+//! let id = device.read_reg(0xd0).unwrap();
+//! device.modify_reg(0xf4, |val| val | 0b11).unwrap();
+//! # }
+//! ```
+
+use super::{Error, I2c, I2cAddress};
+use crate::{Async, Blocking, DriverMode};
+
+/// A device with 8-bit registers at a fixed address.
+pub struct RegisterDevice<'a, 'd, Dm: DriverMode> {
+    i2c: &'a mut I2c<'d, Dm>,
+    address: I2cAddress,
+}
+
+impl<'a, 'd, Dm: DriverMode> RegisterDevice<'a, 'd, Dm> {
+    /// Creates a register device with address `address` on the bus driven by
+    /// `i2c`.
+    pub fn new<A: Into<I2cAddress>>(i2c: &'a mut I2c<'d, Dm>, address: A) -> Self {
+        Self {
+            i2c,
+            address: address.into(),
+        }
+    }
+
+    /// Returns the address of the device.
+    pub fn address(&self) -> I2cAddress {
+        self.address
+    }
+}
+
+impl RegisterDevice<'_, '_, Blocking> {
+    /// Reads the register `reg`.
+    pub fn read_reg(&mut self, reg: u8) -> Result<u8, Error> {
+        let mut val = [0];
+        self.i2c.write_read(self.address, &[reg], &mut val)?;
+        Ok(val[0])
+    }
+
+    /// Writes `val` to the register `reg`.
+    pub fn write_reg(&mut self, reg: u8, val: u8) -> Result<(), Error> {
+        self.i2c.write(self.address, &[reg, val])
+    }
+
+    /// Reads the register `reg`, passes its value to `f` and writes the
+    /// result back.
+    ///
+    /// The read and the write are separate transactions, so another master
+    /// may access the register in between.
+    pub fn modify_reg(&mut self, reg: u8, f: impl FnOnce(u8) -> u8) -> Result<(), Error> {
+        let val = self.read_reg(reg)?;
+        self.write_reg(reg, f(val))
+    }
+
+    /// Reads consecutive registers, starting at `start`, to fill `buffer`.
+    ///
+    /// This relies on the device incrementing the register address after
+    /// each byte, which most devices do.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    pub fn read_regs(&mut self, start: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        self.i2c.write_read(self.address, &[start], buffer)
+    }
+}
+
+impl RegisterDevice<'_, '_, Async> {
+    /// Reads the register `reg`.
+    pub async fn read_reg(&mut self, reg: u8) -> Result<u8, Error> {
+        let mut val = [0];
+        self.i2c.write_read(self.address, &[reg], &mut val).await?;
+        Ok(val[0])
+    }
+
+    /// Writes `val` to the register `reg`.
+    pub async fn write_reg(&mut self, reg: u8, val: u8) -> Result<(), Error> {
+        self.i2c.write(self.address, &[reg, val]).await
+    }
+
+    /// Reads the register `reg`, passes its value to `f` and writes the
+    /// result back.
+    ///
+    /// The read and the write are separate transactions, so another master
+    /// may access the register in between.
+    pub async fn modify_reg(&mut self, reg: u8, f: impl FnOnce(u8) -> u8) -> Result<(), Error> {
+        let val = self.read_reg(reg).await?;
+        self.write_reg(reg, f(val)).await
+    }
+
+    /// Reads consecutive registers, starting at `start`, to fill `buffer`.
+    ///
+    /// This relies on the device incrementing the register address after
+    /// each byte, which most devices do.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    pub async fn read_regs(&mut self, start: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        self.i2c.write_read(self.address, &[start], buffer).await
+    }
+}
@@ -0,0 +1,166 @@
+//! # Shared I2C bus
+//!
+//! [`SharedI2c`] allows multiple async tasks to use the same I2C bus. Each
+//! task gets an [`I2cDevice`] handle that talks to one device on the bus. The
+//! handles implement the `embedded-hal-async` I2C trait, so they can be passed
+//! to device drivers directly.
+//!
+//! ## Example
+//!
+//! ```rust, no_run
+#![doc = crate::before_snippet!()]
+//! # use esp_hal::i2c::master::{Config, I2c, shared::SharedI2c};
+//! let i2c = I2c::new(peripherals.I2C0, Config::default())
+//!     .unwrap()
+//!     .with_sda(peripherals.GPIO1)
+//!     .with_scl(peripherals.GPIO2)
+//!     .into_async();
+//! let bus = SharedI2c::new(i2c);
+//!
+//! let sensor = bus.device(0x77);
+//! let display = bus.device(0x3c);
+//! # }
+//! ```
+
+use embassy_sync::mutex::Mutex;
+use embedded_hal::i2c::Operation as EhalOperation;
+
+use super::{with_timeout, Error, I2c, I2cAddress, Operation, RecoverOnDrop};
+use crate::{sync::RawMutex, Async};
+
+/// An I2C bus shared between async tasks.
+pub struct SharedI2c<'d> {
+    bus: Mutex<RawMutex, I2c<'d, Async>>,
+}
+
+impl<'d> SharedI2c<'d> {
+    /// Creates a shared bus from an async I2C driver.
+    pub fn new(i2c: I2c<'d, Async>) -> Self {
+        Self {
+            bus: Mutex::new(i2c),
+        }
+    }
+
+    /// Returns a handle to the device with address `address` on this bus.
+    pub fn device<A: Into<I2cAddress>>(&self, address: A) -> I2cDevice<'_, 'd> {
+        I2cDevice {
+            bus: &self.bus,
+            address: address.into(),
+        }
+    }
+
+    /// Returns the I2C driver.
+    pub fn into_inner(self) -> I2c<'d, Async> {
+        self.bus.into_inner()
+    }
+}
+
+/// A device on a [`SharedI2c`] bus.
+///
+/// Every operation locks the bus until it has finished, so the operations of
+/// different devices don't interleave. If an operation is cancelled by
+/// dropping its future, a STOP condition is sent and the peripheral is reset
+/// before the bus is released, so the other devices can keep using it.
+///
+/// The inherent methods talk to the device the handle was created for. The
+/// `embedded-hal-async` implementation uses the address passed by the caller,
+/// like any other bus implementation.
+pub struct I2cDevice<'a, 'd> {
+    bus: &'a Mutex<RawMutex, I2c<'d, Async>>,
+    address: I2cAddress,
+}
+
+impl I2cDevice<'_, '_> {
+    /// Returns the address of the device.
+    pub fn address(&self) -> I2cAddress {
+        self.address
+    }
+
+    /// Writes bytes to the device.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    pub async fn write(&mut self, buffer: &[u8]) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.run(self.address, [Operation::Write(buffer)].into_iter())
+            .await
+    }
+
+    /// Reads enough bytes from the device to fill `buffer`.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if `buffer` is empty.
+    pub async fn read(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        if buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.run(self.address, [Operation::Read(buffer)].into_iter())
+            .await
+    }
+
+    /// Writes bytes to the device and then reads enough bytes to fill
+    /// `read_buffer` *in a single transaction*.
+    ///
+    /// Returns [`Error::ZeroLengthInvalid`] if either buffer is empty.
+    pub async fn write_read(
+        &mut self,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if write_buffer.is_empty() || read_buffer.is_empty() {
+            return Err(Error::ZeroLengthInvalid);
+        }
+
+        self.run(
+            self.address,
+            [Operation::Write(write_buffer), Operation::Read(read_buffer)].into_iter(),
+        )
+        .await
+    }
+
+    /// Executes the provided operations on the device as a single
+    /// transaction.
+    ///
+    /// See [`I2c::transaction`] for the transaction contract.
+    pub async fn transaction(&mut self, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        self.run(self.address, operations.iter_mut().map(Operation::from))
+            .await
+    }
+
+    async fn run<'a>(
+        &self,
+        address: I2cAddress,
+        operations: impl Iterator<Item = Operation<'a>>,
+    ) -> Result<(), Error> {
+        let mut bus = self.bus.lock().await;
+
+        // Leave the peripheral in a known state for the next user of the bus if
+        // this future is dropped mid-transaction.
+        let mut guard = RecoverOnDrop(&mut *bus);
+
+        let timeout = guard.0.config.transaction_timeout;
+        let result = with_timeout(timeout, guard.0.transaction_impl_async(address, operations))
+            .await
+            .inspect_err(|_| guard.0.internal_recover());
+
+        core::mem::forget(guard);
+        result
+    }
+}
+
+impl embedded_hal::i2c::ErrorType for I2cDevice<'_, '_> {
+    type Error = Error;
+}
+
+impl embedded_hal_async::i2c::I2c for I2cDevice<'_, '_> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [EhalOperation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.run(address.into(), operations.iter_mut().map(Operation::from))
+            .await
+    }
+}
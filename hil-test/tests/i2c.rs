@@ -7,7 +7,16 @@
 #![no_main]
 
 use esp_hal::{
-    i2c::master::{AcknowledgeCheckFailedReason, Config, Error, I2c, Operation},
+    i2c::master::{
+        AcknowledgeCheckFailedReason,
+        Config,
+        ConfigError,
+        EmptyRead,
+        Error,
+        I2c,
+        Operation,
+    },
+    time::RateExtU32,
     Async,
     Blocking,
 };
@@ -25,6 +34,14 @@ fn _async_driver_is_compatible_with_blocking_ehal() {
     fn _with_ehal(_: impl embedded_hal::i2c::I2c) {}
 }
 
+fn _driver_is_send() {
+    fn _is_send<T: Send>() {}
+
+    // Async drivers are bound to the core their interrupt handler runs on, and
+    // are not `Send`
+    _is_send::<I2c<'static, Blocking>>();
+}
+
 const DUT_ADDRESS: u8 = 0x77;
 const NON_EXISTENT_ADDRESS: u8 = 0x6b;
 
@@ -56,14 +73,16 @@ mod tests {
         cfg_if::cfg_if! {
             if #[cfg(any(esp32,esp32s2,esp32c2,esp32c3))] {
                 assert_eq!(
-                    ctx.i2c.write(NON_EXISTENT_ADDRESS, &[]),
+                    ctx.i2c
+                        .transaction(NON_EXISTENT_ADDRESS, &mut [Operation::Write(&[])]),
                     Err(Error::AcknowledgeCheckFailed(
                         AcknowledgeCheckFailedReason::Unknown
                     ))
                 );
             } else {
                 assert_eq!(
-                    ctx.i2c.write(NON_EXISTENT_ADDRESS, &[]),
+                    ctx.i2c
+                        .transaction(NON_EXISTENT_ADDRESS, &mut [Operation::Write(&[])]),
                     Err(Error::AcknowledgeCheckFailed(
                         AcknowledgeCheckFailedReason::Address
                     ))
@@ -71,7 +90,330 @@ mod tests {
             }
         }
 
-        assert_eq!(ctx.i2c.write(DUT_ADDRESS, &[]), Ok(()));
+        assert_eq!(
+            ctx.i2c
+                .transaction(DUT_ADDRESS, &mut [Operation::Write(&[])]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn empty_buffers_are_rejected(mut ctx: Context) {
+        assert_eq!(
+            ctx.i2c.write(DUT_ADDRESS, &[]),
+            Err(Error::ZeroLengthInvalid)
+        );
+        assert_eq!(
+            ctx.i2c.read(DUT_ADDRESS, &mut []),
+            Err(Error::ZeroLengthInvalid)
+        );
+    }
+
+    #[test]
+    fn reserved_addresses_are_rejected(mut ctx: Context) {
+        assert_eq!(ctx.i2c.write(0x00, &[0xaa]), Err(Error::ReservedAddress));
+        // A left-aligned address
+        assert_eq!(
+            ctx.i2c.write(DUT_ADDRESS << 1, &[0xaa]),
+            Err(Error::ReservedAddress)
+        );
+
+        // Nothing was sent, the device still responds
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn write_without_data_ack_check_still_checks_the_address(mut ctx: Context) {
+        assert!(matches!(
+            ctx.i2c
+                .write_with_data_ack_check(NON_EXISTENT_ADDRESS, &[0xaa], false),
+            Err(Error::AcknowledgeCheckFailed(_))
+        ));
+        assert_eq!(
+            ctx.i2c
+                .write_with_data_ack_check(DUT_ADDRESS, &[0xaa], false),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn error_flags_are_saved_on_failure(mut ctx: Context) {
+        assert!(ctx.i2c.write(NON_EXISTENT_ADDRESS, &[0xaa]).is_err());
+        let flags = ctx.i2c.error_flags();
+        assert!(flags.nack);
+        assert!(!flags.timeout);
+        assert!(!flags.arbitration_lost);
+
+        ctx.i2c.write(DUT_ADDRESS, &[0xaa]).unwrap();
+        assert_eq!(ctx.i2c.error_flags(), Default::default());
+    }
+
+    #[test]
+    fn error_kinds() {
+        use embedded_hal::i2c::{Error as _, ErrorKind, NoAcknowledgeSource};
+
+        assert_eq!(Error::FifoExceeded.kind(), ErrorKind::Overrun);
+        assert_eq!(Error::ArbitrationLost.kind(), ErrorKind::ArbitrationLoss);
+        assert_eq!(
+            Error::AcknowledgeCheckFailed(AcknowledgeCheckFailedReason::Address).kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+        );
+        assert_eq!(
+            Error::AcknowledgeCheckFailed(AcknowledgeCheckFailedReason::Data).kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)
+        );
+        assert_eq!(
+            Error::AcknowledgeCheckFailed(AcknowledgeCheckFailedReason::Unknown).kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+        );
+        assert_eq!(Error::ExecutionIncomplete.kind(), ErrorKind::Bus);
+        assert_eq!(Error::Timeout.kind(), ErrorKind::Other);
+        assert_eq!(Error::ClockStretchTimeout.kind(), ErrorKind::Other);
+        assert_eq!(Error::TransferTooLong { max: 255 }.kind(), ErrorKind::Other);
+        assert_eq!(Error::CommandNumberExceeded.kind(), ErrorKind::Other);
+        assert_eq!(Error::ZeroLengthInvalid.kind(), ErrorKind::Other);
+        assert_eq!(Error::ReservedAddress.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn frequency_extremes(mut ctx: Context) {
+        // The clock divider can't produce a frequency this low
+        assert_eq!(
+            ctx.i2c
+                .apply_config(&Config::default().with_frequency(1.Hz())),
+            Err(ConfigError::FrequencyInvalid)
+        );
+
+        cfg_if::cfg_if! {
+            if #[cfg(any(esp32c2, esp32c3, esp32s3))] {
+                let max_frequency = 800.kHz();
+            } else {
+                let max_frequency = 1.MHz();
+            }
+        }
+        assert_eq!(
+            ctx.i2c
+                .apply_config(&Config::default().with_frequency(max_frequency)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg(not(esp32))]
+    fn scl_wait_high_must_be_shorter_than_scl_high(mut ctx: Context) {
+        assert_eq!(
+            ctx.i2c
+                .apply_config(&Config::default().with_scl_wait_high(Some(u16::MAX))),
+            Err(ConfigError::FrequencyInvalid)
+        );
+
+        assert_eq!(
+            ctx.i2c
+                .apply_config(&Config::default().with_scl_wait_high(Some(10))),
+            Ok(())
+        );
+
+        let mut read_data = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut read_data)
+            .unwrap();
+        assert_ne!(read_data, [0u8; 22]);
+    }
+
+    #[test]
+    fn probe_detects_devices(mut ctx: Context) {
+        assert_eq!(ctx.i2c.probe(NON_EXISTENT_ADDRESS), Ok(false));
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn probe_all_finds_the_device(ctx: Context) {
+        let mut i2c = ctx.i2c.into_async();
+        let present = embassy_futures::block_on(i2c.probe_all());
+
+        let is_present = |address: u8| present[address as usize / 8] & (1 << (address % 8)) != 0;
+        assert!(is_present(DUT_ADDRESS));
+        assert!(!is_present(NON_EXISTENT_ADDRESS));
+
+        // The reserved addresses 0x00..=0x07 and 0x78..=0x7F are not probed
+        assert_eq!(present[0], 0);
+        assert_eq!(present[15], 0);
+    }
+
+    #[test]
+    fn smbus_quick_addresses_the_device(mut ctx: Context) {
+        assert_eq!(ctx.i2c.smbus_quick(DUT_ADDRESS, false), Ok(()));
+        assert!(matches!(
+            ctx.i2c.smbus_quick(NON_EXISTENT_ADDRESS, false),
+            Err(Error::AcknowledgeCheckFailed(_))
+        ));
+
+        // The driver recovered from the NACK
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn smbus_alert_response_without_alert(mut ctx: Context) {
+        // No device on the test bus raises alerts, so none answers the Alert
+        // Response Address
+        assert_eq!(ctx.i2c.smbus_alert_response(), Ok(None));
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn smbus_block_read_uses_the_count_byte(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // The device isn't an SMBus device, so the first calibration byte is
+        // taken as the count
+        let mut out = [0u8; 21];
+        let len = ctx
+            .i2c
+            .smbus_block_read(DUT_ADDRESS, 0xaa, &mut out)
+            .unwrap();
+        assert_eq!(len, (expected[0] as usize).min(out.len()));
+        assert_eq!(out[..len], expected[1..=len]);
+        assert!(!ctx.i2c.is_bus_busy());
+    }
+
+    #[test]
+    fn empty_read_probe_addresses_the_device(mut ctx: Context) {
+        ctx.i2c
+            .apply_config(&Config::default().with_empty_read(EmptyRead::Probe))
+            .unwrap();
+
+        assert_eq!(
+            ctx.i2c
+                .transaction(DUT_ADDRESS, &mut [Operation::Read(&mut [])]),
+            Ok(())
+        );
+        assert!(matches!(
+            ctx.i2c
+                .transaction(NON_EXISTENT_ADDRESS, &mut [Operation::Read(&mut [])]),
+            Err(Error::AcknowledgeCheckFailed(_))
+        ));
+
+        // Only a transaction that consists of the empty read can probe
+        assert_eq!(
+            ctx.i2c.transaction(
+                DUT_ADDRESS,
+                &mut [Operation::Read(&mut []), Operation::Read(&mut [])]
+            ),
+            Err(Error::ZeroLengthInvalid)
+        );
+
+        // The driver recovered from the NACK
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn empty_read_error_rejects_the_transaction(mut ctx: Context) {
+        ctx.i2c
+            .apply_config(&Config::default().with_empty_read(EmptyRead::Error))
+            .unwrap();
+
+        assert_eq!(
+            ctx.i2c
+                .transaction(DUT_ADDRESS, &mut [Operation::Read(&mut [])]),
+            Err(Error::ZeroLengthInvalid)
+        );
+
+        // With the default configuration the empty read is skipped
+        ctx.i2c.apply_config(&Config::default()).unwrap();
+        assert_eq!(
+            ctx.i2c
+                .transaction(DUT_ADDRESS, &mut [Operation::Read(&mut [])]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn device_present_debounces_probes(mut ctx: Context) {
+        assert!(!ctx.i2c.device_present(NON_EXISTENT_ADDRESS, 3));
+        assert!(ctx.i2c.device_present(DUT_ADDRESS, 3));
+        assert!(ctx.i2c.device_present(DUT_ADDRESS, 0));
+    }
+
+    #[test]
+    fn device_present_spaces_out_probes(mut ctx: Context) {
+        // Three agreeing samples take two settle intervals of 5 ms
+        let t1 = esp_hal::time::now();
+        assert!(ctx.i2c.device_present(DUT_ADDRESS, 3));
+        let t2 = esp_hal::time::now();
+        assert!(
+            (t2 - t1).to_millis() >= 10,
+            "diff: {:?}",
+            (t2 - t1).to_millis()
+        );
+
+        // A single sample doesn't wait at all
+        let t1 = esp_hal::time::now();
+        assert!(!ctx.i2c.device_present(NON_EXISTENT_ADDRESS, 1));
+        let t2 = esp_hal::time::now();
+        assert!(
+            (t2 - t1).to_millis() < 5,
+            "diff: {:?}",
+            (t2 - t1).to_millis()
+        );
+
+        // Probing another address in between doesn't disturb the result
+        assert!(ctx.i2c.device_present(DUT_ADDRESS, 2));
+        assert!(!ctx.i2c.device_present(NON_EXISTENT_ADDRESS, 2));
+        assert!(ctx.i2c.device_present(DUT_ADDRESS, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_input_pin_used_by_scl_panics(ctx: Context) {
+        let peripherals = unsafe { esp_hal::peripherals::Peripherals::steal() };
+        let (sda, scl) = hil_test::i2c_pins!(peripherals);
+
+        // The input of SDA is taken from the pin connected to SCL
+        let _i2c = ctx.i2c.with_sda_split(scl, sda);
+    }
+
+    #[test]
+    #[cfg(i2c1)]
+    #[should_panic]
+    fn pin_used_by_another_peripheral_panics(_ctx: Context) {
+        let peripherals = unsafe { esp_hal::peripherals::Peripherals::steal() };
+        let (sda, _) = hil_test::i2c_pins!(peripherals);
+
+        let _i2c1 = I2c::new(peripherals.I2C1, Config::default())
+            .unwrap()
+            .with_sda(sda);
+    }
+
+    #[test]
+    #[cfg(i2c1)]
+    fn pins_are_released_when_the_driver_is_dropped(ctx: Context) {
+        core::mem::drop(ctx);
+
+        let peripherals = unsafe { esp_hal::peripherals::Peripherals::steal() };
+        let (sda, scl) = hil_test::i2c_pins!(peripherals);
+
+        let mut i2c1 = I2c::new(peripherals.I2C1, Config::default())
+            .unwrap()
+            .with_sda(sda)
+            .with_scl(scl);
+        assert_eq!(i2c1.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn check_bus_passes_and_reconnects_pins(mut ctx: Context) {
+        assert_eq!(ctx.i2c.check_bus(), Ok(()));
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn send_stop_on_idle_bus_is_a_no_op(mut ctx: Context) {
+        assert_eq!(ctx.i2c.send_stop(), Ok(()));
+        assert!(!ctx.i2c.is_bus_busy());
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
     }
 
     #[test]
@@ -106,4 +448,247 @@ mod tests {
 
         assert_ne!(read_data, [0u8; 22])
     }
+
+    #[test]
+    fn transaction_with_repeated_starts(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // The FIFOs are only reset at the start of the transaction, so every
+        // direction change must still continue with a repeated START
+        let mut partial = [0u8; 10];
+        let mut head = [0u8; 4];
+        let mut tail = [0u8; 18];
+        ctx.i2c
+            .transaction(
+                DUT_ADDRESS,
+                &mut [
+                    Operation::Write(&[0xaa]),
+                    Operation::Read(&mut partial),
+                    Operation::Write(&[0xaa]),
+                    Operation::Read(&mut head),
+                    Operation::Read(&mut tail),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(partial, expected[..10]);
+        assert_eq!(head, expected[..4]);
+        assert_eq!(tail, expected[4..]);
+    }
+
+    #[test]
+    fn transaction_no_stop_holds_the_bus(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        ctx.i2c
+            .transaction_no_stop(DUT_ADDRESS, &mut [Operation::Write(&[0xaa])])
+            .unwrap();
+        assert!(ctx.i2c.is_bus_busy());
+
+        // Continues with a repeated START and releases the bus
+        let mut read_data = [0u8; 22];
+        ctx.i2c
+            .transaction(DUT_ADDRESS, &mut [Operation::Read(&mut read_data)])
+            .unwrap();
+        assert_eq!(read_data, expected);
+        assert!(!ctx.i2c.is_bus_busy());
+
+        ctx.i2c
+            .transaction_no_stop(DUT_ADDRESS, &mut [Operation::Write(&[0xaa])])
+            .unwrap();
+        ctx.i2c.send_stop().unwrap();
+        assert!(!ctx.i2c.is_bus_busy());
+    }
+
+    #[test]
+    fn transaction_detailed_reports_the_failed_operation(mut ctx: Context) {
+        let error = ctx
+            .i2c
+            .transaction_detailed(
+                NON_EXISTENT_ADDRESS,
+                &mut [Operation::Delay(10), Operation::Write(&[0xaa])],
+            )
+            .unwrap_err();
+        assert!(matches!(error.error, Error::AcknowledgeCheckFailed(_)));
+        assert_eq!(error.operation, 1);
+        assert_eq!(error.transferred, 0);
+
+        // The driver recovered from the error
+        let mut read_data = [0u8; 22];
+        assert_eq!(
+            ctx.i2c.transaction_detailed(
+                DUT_ADDRESS,
+                &mut [Operation::Write(&[0xaa]), Operation::Read(&mut read_data)],
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn coalesced_transactions_continue_with_repeated_start(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        ctx.i2c
+            .apply_config(&Config::default().with_coalesce_transactions(true))
+            .unwrap();
+
+        ctx.i2c.write(DUT_ADDRESS, &[0xaa]).unwrap();
+        assert!(ctx.i2c.is_bus_held());
+
+        let mut read_data = [0u8; 22];
+        ctx.i2c.read(DUT_ADDRESS, &mut read_data).unwrap();
+        assert_eq!(read_data, expected);
+        assert!(ctx.i2c.is_bus_held());
+
+        ctx.i2c.send_stop().unwrap();
+        assert!(!ctx.i2c.is_bus_held());
+        assert!(!ctx.i2c.is_bus_busy());
+    }
+
+    #[test]
+    fn cancelled_write_read_releases_the_bus(ctx: Context) {
+        let mut i2c = ctx.i2c.into_async();
+
+        let mut expected = [0u8; 22];
+        embassy_futures::block_on(i2c.write_read(DUT_ADDRESS, &[0xaa], &mut expected)).unwrap();
+
+        let mut read_data = [0u8; 22];
+        {
+            let write_read = core::pin::pin!(i2c.write_read(DUT_ADDRESS, &[0xaa], &mut read_data));
+            // The first poll starts the write, and the future is dropped while
+            // waiting for it to complete
+            assert!(embassy_futures::poll_once(write_read).is_pending());
+        }
+        assert!(!i2c.is_bus_busy());
+
+        let mut read_data = [0u8; 22];
+        embassy_futures::block_on(i2c.write_read(DUT_ADDRESS, &[0xaa], &mut read_data)).unwrap();
+        assert_eq!(read_data, expected);
+    }
+
+    #[test]
+    fn adjacent_reads_are_one_continuous_read(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // Only the last byte of the second read may be NACKed. If the first
+        // read ended with a NACK, the device would stop sending and the second
+        // buffer would not continue where the first one ended.
+        let mut first = [0u8; 10];
+        let mut second = [0u8; 12];
+        ctx.i2c
+            .transaction(
+                DUT_ADDRESS,
+                &mut [
+                    Operation::Write(&[0xaa]),
+                    Operation::Read(&mut first),
+                    Operation::Read(&mut second),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(first, expected[..10]);
+        assert_eq!(second, expected[10..]);
+
+        // The final NACK and STOP released the bus
+        assert!(!ctx.i2c.is_bus_busy());
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn write_longer_than_the_fifo_is_chunked(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // Longer than the FIFO of the ESP32 and ESP32-S2. The device ignores
+        // writes to its read-only calibration registers.
+        ctx.i2c.write(DUT_ADDRESS, &[0xaa; 40]).unwrap();
+
+        let mut read_data = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut read_data)
+            .unwrap();
+        assert_eq!(read_data, expected);
+    }
+
+    #[test]
+    fn write_iter_spans_several_chunks(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // Longer than a chunk on every chip
+        ctx.i2c
+            .write_iter(DUT_ADDRESS, core::iter::repeat(0xaa).take(300))
+            .unwrap();
+        assert!(!ctx.i2c.is_bus_busy());
+
+        let mut read_data = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut read_data)
+            .unwrap();
+        assert_eq!(read_data, expected);
+    }
+
+    #[test]
+    fn read_with_last_ack(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // NACKing the last byte behaves like a plain read
+        let mut read_data = [0u8; 22];
+        ctx.i2c.write(DUT_ADDRESS, &[0xaa]).unwrap();
+        ctx.i2c
+            .read_with_last_ack(DUT_ADDRESS, &mut read_data, false)
+            .unwrap();
+        assert_eq!(read_data, expected);
+        assert!(!ctx.i2c.is_bus_busy());
+
+        // After an ACK the device starts sending the next byte, which can keep
+        // the STOP condition from being sent. The data read up to then is the
+        // same, and the driver recovers either way.
+        let mut read_data = [0u8; 22];
+        ctx.i2c.write(DUT_ADDRESS, &[0xaa]).unwrap();
+        if ctx
+            .i2c
+            .read_with_last_ack(DUT_ADDRESS, &mut read_data, true)
+            .is_ok()
+        {
+            assert_eq!(read_data, expected);
+        }
+        assert_eq!(ctx.i2c.probe(DUT_ADDRESS), Ok(true));
+    }
+
+    #[test]
+    fn read_of_full_command_length_is_chunked(mut ctx: Context) {
+        let mut expected = [0u8; 22];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0xaa], &mut expected)
+            .unwrap();
+
+        // 255 bytes fit into a continuing read chunk, but not into the last
+        // one, which needs a separate command for the NACKed byte
+        let mut read_data = [0u8; 255];
+        ctx.i2c
+            .write_read(DUT_ADDRESS, &[0x00], &mut read_data)
+            .unwrap();
+
+        assert_eq!(read_data[0xaa..0xaa + 22], expected);
+    }
 }